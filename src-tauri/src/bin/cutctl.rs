@@ -0,0 +1,166 @@
+//! cutctl：剪切板助手命令行客户端
+//!
+//! 通过本地脚本接口操作剪切板历史，例如：
+//!
+//! ```sh
+//! cutctl search foo | fzf | cutctl copy
+//! ```
+
+use interprocess::local_socket::{prelude::*, Stream};
+use serde_json::{json, Value};
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::process::ExitCode;
+
+use cut_lib::ipc::{self, RpcResponse};
+
+const USAGE: &str = "用法: cutctl <命令> [参数]
+
+命令:
+  list [-n <条数>]              按设置中的排序方式列出文本历史（每行: ID<TAB>内容预览）
+  search <关键词> [-n <条数>]   搜索文本历史，排序方式同 list
+  get [ID]                      输出一条记录的完整内容
+  add [内容]                    添加一条记录，省略内容时从标准输入读取
+  copy [ID]                     复制一条记录到剪切板
+  delete [ID]                   把一条记录移到回收站
+  groups                        列出分组（每行: ID<TAB>名称）
+
+省略 ID 时从标准输入读取第一行，取第一个制表符前的部分，
+因此可以直接接收 list / search 的输出";
+
+/// 预览的最大字符数
+const PREVIEW_CHARS: usize = 200;
+
+/// 内部函数：发送一个请求并返回结果
+fn call(method: &str, params: Value) -> Result<Value, String> {
+    let app_data_dir = ipc::default_app_data_dir().ok_or("无法确定应用数据目录")?;
+    let name = ipc::endpoint(&app_data_dir).map_err(|e| format!("无效的连接地址: {}", e))?;
+    let stream = Stream::connect(name).map_err(|e| format!("无法连接剪切板助手（是否已启动？）: {}", e))?;
+    let mut stream = BufReader::new(stream);
+
+    let request = json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params });
+    let mut payload = request.to_string();
+    payload.push('\n');
+    stream
+        .get_mut()
+        .write_all(payload.as_bytes())
+        .map_err(|e| format!("发送请求失败: {}", e))?;
+
+    let mut line = String::new();
+    stream.read_line(&mut line).map_err(|e| format!("读取响应失败: {}", e))?;
+    let response: RpcResponse = serde_json::from_str(&line).map_err(|e| format!("无效的响应: {}", e))?;
+
+    match (response.result, response.error) {
+        (_, Some(error)) => Err(error.message),
+        (result, None) => Ok(result.unwrap_or(Value::Null)),
+    }
+}
+
+/// 内部函数：参数中的 ID，缺省时从标准输入第一行读取
+fn read_id(arg: Option<&String>) -> Result<String, String> {
+    let line = match arg {
+        Some(arg) => arg.clone(),
+        None => {
+            let mut line = String::new();
+            io::stdin().lock().read_line(&mut line).map_err(|e| format!("读取标准输入失败: {}", e))?;
+            line
+        }
+    };
+
+    let id = line.split('\t').next().unwrap_or_default().trim();
+    if id.is_empty() {
+        return Err("缺少记录ID".to_string());
+    }
+    Ok(id.to_string())
+}
+
+/// 内部函数：解析 -n <条数>
+fn read_limit(args: &[String]) -> Result<Option<u32>, String> {
+    match args.iter().position(|arg| arg == "-n") {
+        Some(index) => args
+            .get(index + 1)
+            .and_then(|n| n.parse().ok())
+            .map(Some)
+            .ok_or_else(|| "-n 需要一个正整数".to_string()),
+        None => Ok(None),
+    }
+}
+
+/// 内部函数：输出 ID<TAB>单行预览
+fn print_rows(rows: &Value, text_field: &str) {
+    let stdout = io::stdout();
+    let mut out = stdout.lock();
+    for row in rows.as_array().into_iter().flatten() {
+        let id = row["id"].as_str().unwrap_or_default();
+        let text = row[text_field].as_str().unwrap_or_default();
+        let preview: String = text
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ")
+            .chars()
+            .take(PREVIEW_CHARS)
+            .collect();
+        if writeln!(out, "{}\t{}", id, preview).is_err() {
+            // 下游管道已关闭（例如 head）
+            break;
+        }
+    }
+}
+
+fn run(args: &[String]) -> Result<(), String> {
+    let command = args.first().map(String::as_str);
+    let rest = args.get(1..).unwrap_or_default();
+
+    match command {
+        Some("list") => {
+            let rows = call("list", json!({ "limit": read_limit(rest)? }))?;
+            print_rows(&rows, "content");
+        }
+        Some("search") => {
+            let keyword = rest.first().filter(|arg| *arg != "-n").ok_or("缺少搜索关键词")?;
+            let rows = call("search", json!({ "keyword": keyword, "limit": read_limit(rest)? }))?;
+            print_rows(&rows, "content");
+        }
+        Some("get") => {
+            let item = call("get", json!({ "id": read_id(rest.first())? }))?;
+            println!("{}", item["content"].as_str().unwrap_or_default());
+        }
+        Some("add") => {
+            let content = match rest.first() {
+                Some(content) => content.clone(),
+                None => {
+                    let mut content = String::new();
+                    io::stdin().read_to_string(&mut content).map_err(|e| format!("读取标准输入失败: {}", e))?;
+                    content
+                }
+            };
+            let item = call("add", json!({ "content": content }))?;
+            println!("{}", item["id"].as_str().unwrap_or_default());
+        }
+        Some("copy") => {
+            call("copy", json!({ "id": read_id(rest.first())? }))?;
+        }
+        Some("delete") => {
+            call("delete", json!({ "id": read_id(rest.first())? }))?;
+        }
+        Some("groups") => {
+            let rows = call("groups", Value::Null)?;
+            print_rows(&rows, "name");
+        }
+        Some("-h") | Some("--help") | Some("help") => println!("{}", USAGE),
+        _ => return Err(USAGE.to_string()),
+    }
+
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+
+    match run(&args) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,81 @@
+use tauri::AppHandle;
+
+use crate::commands::paste;
+use crate::window_manager;
+
+/// 启动参数对应的操作
+///
+/// 桌面环境可以把这些参数绑定到快捷键上，重复启动时由已运行的实例执行
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LaunchAction {
+    /// --show：显示并聚焦主窗口
+    Show,
+    /// --toggle：切换主窗口显示状态
+    Toggle,
+    /// --settings：打开设置窗口
+    Settings,
+    /// --paste <n>：粘贴第 n 条文本历史
+    Paste(u32),
+}
+
+/// 解析启动参数（不含程序名），忽略无法识别的参数
+pub fn parse_args<S: AsRef<str>>(args: &[S]) -> Vec<LaunchAction> {
+    let mut actions = Vec::new();
+    let mut iter = args.iter().map(AsRef::as_ref);
+
+    while let Some(arg) = iter.next() {
+        match arg {
+            "--show" => actions.push(LaunchAction::Show),
+            "--toggle" => actions.push(LaunchAction::Toggle),
+            "--settings" => actions.push(LaunchAction::Settings),
+            "--paste" => match iter.next().and_then(|n| n.parse().ok()) {
+                Some(n) => actions.push(LaunchAction::Paste(n)),
+                None => tracing::warn!("--paste requires a positive history index"),
+            },
+            _ => {}
+        }
+    }
+
+    actions
+}
+
+/// 是否以后台模式启动（--minimized / --background，开机自启动时传入）
+///
+/// 后台模式下主窗口保持隐藏，剪切板监控照常运行
+pub fn is_background_launch<S: AsRef<str>>(args: &[S]) -> bool {
+    args.iter().any(|arg| matches!(arg.as_ref(), "--minimized" | "--background"))
+}
+
+/// 执行启动参数对应的操作
+pub fn run_action(app: &AppHandle, action: LaunchAction) {
+    let result = match action {
+        LaunchAction::Show => window_manager::show_main(app),
+        LaunchAction::Toggle => window_manager::toggle_main(app),
+        LaunchAction::Settings => window_manager::show_settings(app),
+        LaunchAction::Paste(n) => {
+            let app = app.clone();
+            tauri::async_runtime::spawn(async move {
+                if let Err(e) = paste::paste_recent(&app, n).await {
+                    tracing::error!("Failed to paste history item {}: {}", n, e);
+                }
+            });
+            Ok(())
+        }
+    };
+    if let Err(e) = result {
+        tracing::error!("Failed to run launch action {:?}: {}", action, e);
+    }
+}
+
+/// 处理重复启动时转发过来的参数（第一个参数为程序路径）
+///
+/// 没有可识别的参数时显示并聚焦已运行实例的主窗口
+pub fn handle_second_instance(app: &AppHandle, args: &[String]) {
+    let actions = parse_args(args.get(1..).unwrap_or_default());
+    if actions.is_empty() {
+        run_action(app, LaunchAction::Show);
+    }
+    for action in actions {
+        run_action(app, action);
+    }
+}
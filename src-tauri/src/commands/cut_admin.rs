@@ -1,24 +1,91 @@
-use tauri::{Runtime, Manager};
+use tauri::{AppHandle, Emitter, Runtime, Manager};
+
+use crate::config::AppConfig;
+use crate::db::{self, CutItem, Trash, TrashedItem};
+use crate::error::{AppError, AppResult};
 
 #[tauri::command]
 pub async fn test_fun<R: Runtime>(
     _app: tauri::AppHandle<R>,
     _window: tauri::Window<R>,
-) -> Result<(), String> {
-    println!("测试函数被调用");
+) -> AppResult<()> {
+    tracing::info!("测试函数被调用");
     Ok(())
 }
 
 #[tauri::command]
 pub async fn get_db_path<R: Runtime>(
     app: tauri::AppHandle<R>,
-) -> Result<String, String> {
+) -> AppResult<String> {
     // 获取应用数据目录
     let app_data_dir = app.path().app_data_dir()
-        .map_err(|e| format!("Failed to get app data dir: {}", e))?;
+        .map_err(|e| AppError::Io(format!("Failed to get app data dir: {}", e)))?;
     
     // 数据库文件路径
     let db_path = app_data_dir.join("cut.db");
     
     Ok(db_path.to_string_lossy().to_string())
 }
+
+/// 记录剪切板中的文本（前端监控到新的剪切板内容时调用）
+///
+/// 相同内容已存在时只把该记录移到最前并增加记录次数
+#[tauri::command]
+pub async fn add_text_item(app: AppHandle, content: String) -> AppResult<CutItem> {
+    db::add_cut_item(&app, &content, db::SELECTION_CLIPBOARD).await
+}
+
+/// 按配置的排序方式（最近 / frecency）查询文本历史（不含回收站）
+#[tauri::command]
+pub async fn list_text_items(app: AppHandle) -> AppResult<Vec<CutItem>> {
+    let pool = db::pool(&app).await?;
+    let sort = AppConfig::current(&app)?.history_sort;
+    db::query_cut_items(&pool, None, None, sort).await
+}
+
+/// 读取文本历史或分组内容的完整内容（压缩存储的记录在列表中只有预览）
+#[tauri::command]
+pub async fn get_item_content(app: AppHandle, id: String) -> AppResult<String> {
+    let pool = db::pool(&app).await?;
+    db::get_item_content(&pool, &id).await
+}
+
+/// 按配置的保留数量清理最旧的文本和图片历史（前端记录图片后调用，回收站中的记录不计入数量）
+#[tauri::command]
+pub async fn enforce_history_limits(app: AppHandle) -> AppResult<()> {
+    let pool = db::pool(&app).await?;
+    let config = AppConfig::current(&app)?;
+    db::enforce_retention(&pool, config.max_text_history, config.max_image_history).await
+}
+
+/// 把文本或图片记录移到回收站，并通知前端（可用于提示撤销）
+#[tauri::command]
+pub async fn delete_item(app: AppHandle, id: String) -> AppResult<()> {
+    let pool = db::pool(&app).await?;
+    let item_type = db::trash_item(&pool, &id).await?;
+    app.emit(db::ITEM_TRASHED_EVENT, TrashedItem { id, item_type })?;
+    Ok(())
+}
+
+/// 从回收站恢复记录
+#[tauri::command]
+pub async fn restore_item(app: AppHandle, id: String) -> AppResult<()> {
+    let pool = db::pool(&app).await?;
+    let item_type = db::restore_trashed_item(&pool, &id).await?;
+    app.emit(db::ITEM_RESTORED_EVENT, TrashedItem { id, item_type })?;
+    Ok(())
+}
+
+/// 查询回收站中的记录
+#[tauri::command]
+pub async fn list_trash(app: AppHandle) -> AppResult<Trash> {
+    let pool = db::pool(&app).await?;
+    db::list_trash(&pool).await
+}
+
+/// 清空回收站，返回永久删除的条数
+#[tauri::command]
+pub async fn empty_trash(app: AppHandle) -> AppResult<u64> {
+    let pool = db::pool(&app).await?;
+    db::purge_trash(&pool, None).await
+}
@@ -0,0 +1,292 @@
+use serde::{Deserialize, Serialize};
+use sqlx::sqlite::SqlitePool;
+use tauri::AppHandle;
+
+use crate::config::AppConfig;
+use crate::db;
+use crate::error::{AppError, AppResult};
+
+/// AppState 中保存上次整理时间的键
+const STATE_LAST_VACUUM: &str = "lastVacuumAt";
+
+/// 统计的表：表名、内容字节数表达式、时间列
+const STAT_TABLES: &[(&str, &str, &str)] = &[
+    ("CutItems", "length(CAST(content AS BLOB)) + COALESCE(length(contentBlob), 0)", "createTime"),
+    ("ImageItems", "length(CAST(content AS BLOB)) + COALESCE(length(CAST(ocrText AS BLOB)), 0)", "createTime"),
+    ("Groups", "length(CAST(name AS BLOB))", "createTime"),
+    ("GroupItems", "length(CAST(content AS BLOB)) + COALESCE(length(CAST(title AS BLOB)), 0)", "createTime"),
+    ("ItemRevisions", "length(CAST(content AS BLOB)) + COALESCE(length(contentBlob), 0)", "createTime"),
+    ("Tags", "length(CAST(name AS BLOB))", "createTime"),
+    ("SyncOutbox", "length(CAST(entityId AS BLOB))", "time"),
+];
+
+/// 单个表的统计
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct TableStats {
+    pub name: String,
+    pub rows: i64,
+    /// 内容列的字节数（不含索引和页面开销）
+    pub bytes: i64,
+    pub oldest: Option<String>,
+    pub newest: Option<String>,
+}
+
+/// 图片存储统计
+#[derive(Debug, Clone, Default, Serialize, Deserialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct ImageStats {
+    pub count: i64,
+    /// 原始 RGBA 数据大小合计
+    pub original_bytes: i64,
+    /// 编码后的图片大小合计
+    pub encoded_bytes: i64,
+    /// 数据库中保存的 data URL 大小合计
+    pub stored_bytes: i64,
+}
+
+/// 数据库统计
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DbStats {
+    /// 数据库文件大小（页数 × 页大小）
+    pub file_size: i64,
+    /// 空闲页占用的大小，整理（VACUUM）后可以释放
+    pub free_size: i64,
+    pub tables: Vec<TableStats>,
+    pub images: ImageStats,
+    /// 回收站中的记录数
+    pub trash_count: i64,
+    /// 上次整理时间
+    pub last_vacuum_at: Option<String>,
+}
+
+/// 整理结果
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VacuumReport {
+    pub size_before: i64,
+    pub size_after: i64,
+}
+
+/// 完整性检查结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IntegrityReport {
+    /// PRAGMA integrity_check 没有发现问题且没有孤立记录
+    pub ok: bool,
+    /// PRAGMA integrity_check 的输出（没有问题时为空）
+    pub problems: Vec<String>,
+    /// 所属分组已不存在的分组内容 ID
+    pub orphaned_group_items: Vec<String>,
+}
+
+/// 内部函数：数据库文件大小和空闲大小
+async fn file_sizes(pool: &SqlitePool) -> AppResult<(i64, i64)> {
+    let page_size: i64 = sqlx::query_scalar("PRAGMA page_size").fetch_one(pool).await?;
+    let page_count: i64 = sqlx::query_scalar("PRAGMA page_count").fetch_one(pool).await?;
+    let free_pages: i64 = sqlx::query_scalar("PRAGMA freelist_count").fetch_one(pool).await?;
+    Ok((page_count * page_size, free_pages * page_size))
+}
+
+/// 内部函数：读取 AppState 中的值
+async fn get_state(pool: &SqlitePool, key: &str) -> AppResult<Option<String>> {
+    sqlx::query_scalar("SELECT value FROM AppState WHERE key = ?")
+        .bind(key)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| AppError::Database(format!("Failed to read app state: {}", e)))
+}
+
+/// 统计数据库的大小、各表记录数和时间范围
+pub async fn collect_stats(pool: &SqlitePool) -> AppResult<DbStats> {
+    let (file_size, free_size) = file_sizes(pool).await?;
+
+    let mut tables = Vec::with_capacity(STAT_TABLES.len());
+    for (table, bytes, time) in STAT_TABLES {
+        let sql = format!(
+            "SELECT '{table}' AS name, COUNT(*) AS rows, COALESCE(SUM({bytes}), 0) AS bytes,
+             MIN({time}) AS oldest, MAX({time}) AS newest FROM {table}"
+        );
+        let stats = sqlx::query_as::<_, TableStats>(&sql)
+            .fetch_one(pool)
+            .await
+            .map_err(|e| AppError::Database(format!("Failed to collect stats for {}: {}", table, e)))?;
+        tables.push(stats);
+    }
+
+    let images = sqlx::query_as::<_, ImageStats>(
+        "SELECT COUNT(*) AS count, COALESCE(SUM(size), 0) AS original_bytes,
+         COALESCE(SUM(byteSize), 0) AS encoded_bytes, COALESCE(SUM(length(content)), 0) AS stored_bytes
+         FROM ImageItems",
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(|e| AppError::Database(format!("Failed to collect image stats: {}", e)))?;
+
+    let trash_count: i64 = sqlx::query_scalar(
+        "SELECT (SELECT COUNT(*) FROM CutItems WHERE deletedAt IS NOT NULL)
+              + (SELECT COUNT(*) FROM ImageItems WHERE deletedAt IS NOT NULL)",
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(|e| AppError::Database(format!("Failed to count trash: {}", e)))?;
+
+    Ok(DbStats {
+        file_size,
+        free_size,
+        tables,
+        images,
+        trash_count,
+        last_vacuum_at: get_state(pool, STATE_LAST_VACUUM).await?,
+    })
+}
+
+/// 整理数据库（VACUUM），释放空闲页并记录整理时间
+pub async fn vacuum(pool: &SqlitePool) -> AppResult<VacuumReport> {
+    let (size_before, _) = file_sizes(pool).await?;
+
+    sqlx::query("VACUUM")
+        .execute(pool)
+        .await
+        .map_err(|e| AppError::Database(format!("Failed to vacuum database: {}", e)))?;
+
+    sqlx::query("INSERT OR REPLACE INTO AppState (key, value) VALUES (?, ?)")
+        .bind(STATE_LAST_VACUUM)
+        .bind(db::now_string())
+        .execute(pool)
+        .await
+        .map_err(|e| AppError::Database(format!("Failed to save app state: {}", e)))?;
+
+    let (size_after, _) = file_sizes(pool).await?;
+    tracing::info!("Vacuumed database: {} -> {} bytes", size_before, size_after);
+    Ok(VacuumReport { size_before, size_after })
+}
+
+/// 开启自动整理且距上次整理超过间隔天数时整理数据库
+pub async fn auto_vacuum_if_due(pool: &SqlitePool, config: &AppConfig) -> AppResult<()> {
+    if !config.auto_vacuum_enabled {
+        return Ok(());
+    }
+
+    let cutoff = chrono::Utc::now() - chrono::Duration::days(config.auto_vacuum_interval_days as i64);
+    let cutoff = cutoff.to_rfc3339_opts(chrono::SecondsFormat::Millis, true);
+    match get_state(pool, STATE_LAST_VACUUM).await? {
+        Some(last) if last > cutoff => Ok(()),
+        _ => vacuum(pool).await.map(|_| ()),
+    }
+}
+
+/// 检查数据库完整性（PRAGMA integrity_check）并查找孤立的分组内容
+pub async fn integrity_check(pool: &SqlitePool) -> AppResult<IntegrityReport> {
+    let mut problems: Vec<String> = sqlx::query_scalar("PRAGMA integrity_check")
+        .fetch_all(pool)
+        .await
+        .map_err(|e| AppError::Database(format!("Failed to check integrity: {}", e)))?;
+    problems.retain(|line| line != "ok");
+    for problem in &problems {
+        tracing::warn!("Integrity check: {}", problem);
+    }
+
+    let orphaned_group_items: Vec<String> = sqlx::query_scalar(
+        "SELECT id FROM GroupItems WHERE groupId NOT IN (SELECT id FROM Groups) ORDER BY createTime",
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| AppError::Database(format!("Failed to query orphaned group items: {}", e)))?;
+
+    Ok(IntegrityReport {
+        ok: problems.is_empty() && orphaned_group_items.is_empty(),
+        problems,
+        orphaned_group_items,
+    })
+}
+
+/// 查询数据库统计信息
+#[tauri::command]
+pub async fn db_stats(app: AppHandle) -> AppResult<DbStats> {
+    let pool = db::pool(&app).await?;
+    collect_stats(&pool).await
+}
+
+/// 整理数据库，返回整理前后的文件大小
+#[tauri::command]
+pub async fn vacuum_database(app: AppHandle) -> AppResult<VacuumReport> {
+    let pool = db::pool(&app).await?;
+    vacuum(&pool).await
+}
+
+/// 检查数据库完整性并查找孤立的分组内容
+#[tauri::command]
+pub async fn check_integrity(app: AppHandle) -> AppResult<IntegrityReport> {
+    let pool = db::pool(&app).await?;
+    integrity_check(&pool).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::migrations;
+
+    fn table<'a>(stats: &'a DbStats, name: &str) -> &'a TableStats {
+        stats.tables.iter().find(|table| table.name == name).unwrap()
+    }
+
+    #[tokio::test]
+    async fn stats_count_rows_and_trash() {
+        let pool = migrations::migrated_pool().await;
+        let kept = db::insert_cut_item(&pool, "保留", db::SELECTION_CLIPBOARD, usize::MAX).await.unwrap();
+        let trashed = db::insert_cut_item(&pool, "删除", db::SELECTION_CLIPBOARD, usize::MAX).await.unwrap();
+        db::trash_item(&pool, &trashed.id).await.unwrap();
+
+        let stats = collect_stats(&pool).await.unwrap();
+        assert!(stats.file_size > 0);
+        assert_eq!(stats.trash_count, 1);
+        assert_eq!(stats.last_vacuum_at, None);
+
+        let cut_items = table(&stats, "CutItems");
+        assert_eq!(cut_items.rows, 2);
+        assert_eq!(cut_items.bytes, ("保留".len() + "删除".len()) as i64);
+        assert_eq!(cut_items.oldest.as_deref(), Some(kept.create_time.as_str()));
+        assert_eq!(table(&stats, "ImageItems").rows, 0);
+        assert_eq!(stats.images.count, 0);
+    }
+
+    #[tokio::test]
+    async fn vacuum_records_time() {
+        let pool = migrations::migrated_pool().await;
+        let report = vacuum(&pool).await.unwrap();
+        assert!(report.size_after > 0);
+
+        let last = collect_stats(&pool).await.unwrap().last_vacuum_at;
+        assert!(last.is_some());
+
+        // 间隔内不再自动整理
+        tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+        let config = AppConfig { auto_vacuum_enabled: true, ..AppConfig::default() };
+        auto_vacuum_if_due(&pool, &config).await.unwrap();
+        assert_eq!(collect_stats(&pool).await.unwrap().last_vacuum_at, last);
+    }
+
+    #[tokio::test]
+    async fn integrity_check_finds_orphans() {
+        let pool = migrations::migrated_pool().await;
+        let report = integrity_check(&pool).await.unwrap();
+        assert!(report.ok);
+        assert!(report.problems.is_empty());
+
+        // 外键约束之前留下的孤立分组内容
+        sqlx::raw_sql(
+            "PRAGMA foreign_keys = OFF;
+             INSERT INTO GroupItems (id, groupId, content, createTime) VALUES ('gi1', 'missing', '孤立内容', '2024-01-01T00:00:00.000Z');
+             PRAGMA foreign_keys = ON;",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let report = integrity_check(&pool).await.unwrap();
+        assert!(!report.ok);
+        assert_eq!(report.orphaned_group_items, vec!["gi1".to_string()]);
+    }
+}
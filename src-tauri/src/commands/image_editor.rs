@@ -0,0 +1,362 @@
+use image::imageops::{self, FilterType};
+use image::{DynamicImage, Rgba, RgbaImage};
+use imageproc::drawing::{draw_filled_rect_mut, draw_hollow_rect_mut, draw_line_segment_mut, draw_polygon_mut};
+use imageproc::point::Point;
+use imageproc::rect::Rect;
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+use crate::commands::image_processor::{decode_data_url, encode_image, parse_hex_color, ImageEncoding};
+use crate::db::{self, ImageItem};
+use crate::error::{AppError, AppResult};
+
+/// 缩放目标的最大边长（与 WebP 的尺寸上限一致），避免一次请求分配过大的内存
+const MAX_RESIZE_SIDE: u32 = 16384;
+
+/// 标注线条的最大粗细
+const MAX_THICKNESS: u32 = 100;
+
+/// 标注线条的默认粗细
+const DEFAULT_THICKNESS: u32 = 3;
+
+/// 缩放滤波器
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ResizeFilter {
+    Nearest,
+    /// 性能与质量平衡
+    #[default]
+    Triangle,
+    CatmullRom,
+    Gaussian,
+    /// 质量最高，速度最慢
+    Lanczos3,
+}
+
+impl From<ResizeFilter> for FilterType {
+    fn from(filter: ResizeFilter) -> Self {
+        match filter {
+            ResizeFilter::Nearest => FilterType::Nearest,
+            ResizeFilter::Triangle => FilterType::Triangle,
+            ResizeFilter::CatmullRom => FilterType::CatmullRom,
+            ResizeFilter::Gaussian => FilterType::Gaussian,
+            ResizeFilter::Lanczos3 => FilterType::Lanczos3,
+        }
+    }
+}
+
+/// 翻转方向
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FlipDirection {
+    Horizontal,
+    Vertical,
+}
+
+/// 图片编辑操作，按顺序依次应用
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ImageEdit {
+    /// 裁剪
+    Crop { x: u32, y: u32, width: u32, height: u32 },
+    /// 缩放，keep_aspect 为 true 时在给定尺寸内等比缩放
+    Resize {
+        width: u32,
+        height: u32,
+        #[serde(default)]
+        filter: ResizeFilter,
+        #[serde(default)]
+        keep_aspect: bool,
+    },
+    /// 顺时针旋转（90 / 180 / 270 度）
+    Rotate { degrees: u32 },
+    /// 翻转
+    Flip { direction: FlipDirection },
+    /// 模糊区域（用于打码）
+    Blur {
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+        sigma: Option<f32>,
+    },
+    /// 绘制矩形框
+    Rectangle {
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+        color: Option<String>,
+        thickness: Option<u32>,
+    },
+    /// 绘制箭头
+    Arrow {
+        from_x: f32,
+        from_y: f32,
+        to_x: f32,
+        to_y: f32,
+        color: Option<String>,
+        thickness: Option<u32>,
+    },
+}
+
+/// 内部函数：解析标注颜色，默认红色
+fn parse_color(color: Option<&str>) -> AppResult<Rgba<u8>> {
+    parse_hex_color(color.unwrap_or("#FF0000"))
+}
+
+/// 内部函数：检查区域是否在图片范围内
+fn check_region(img: &DynamicImage, x: u32, y: u32, width: u32, height: u32) -> AppResult<()> {
+    let in_bounds = width > 0
+        && height > 0
+        && x.checked_add(width).is_some_and(|right| right <= img.width())
+        && y.checked_add(height).is_some_and(|bottom| bottom <= img.height());
+
+    if in_bounds {
+        Ok(())
+    } else {
+        Err(AppError::InvalidInput(format!(
+            "Region {}x{}+{}+{} is outside the image ({}x{})",
+            width,
+            height,
+            x,
+            y,
+            img.width(),
+            img.height()
+        )))
+    }
+}
+
+/// 内部函数：检查标注线条粗细，默认 3，0 按 1 处理
+fn check_thickness(thickness: Option<u32>) -> AppResult<u32> {
+    match thickness.unwrap_or(DEFAULT_THICKNESS) {
+        thickness if thickness > MAX_THICKNESS => Err(AppError::InvalidInput(format!(
+            "Thickness {} exceeds the maximum of {}",
+            thickness, MAX_THICKNESS
+        ))),
+        thickness => Ok(thickness.max(1)),
+    }
+}
+
+/// 内部函数：把箭头端点限制在画布范围内，坐标不是有限数时返回错误
+fn clip_point(canvas: &RgbaImage, x: f32, y: f32) -> AppResult<(f32, f32)> {
+    if !x.is_finite() || !y.is_finite() {
+        return Err(AppError::InvalidInput(format!("Invalid arrow point ({}, {})", x, y)));
+    }
+    Ok((x.clamp(0.0, canvas.width() as f32), y.clamp(0.0, canvas.height() as f32)))
+}
+
+/// 内部函数：绘制带粗细的箭头
+fn draw_arrow(canvas: &mut RgbaImage, from: (f32, f32), to: (f32, f32), color: Rgba<u8>, thickness: u32) {
+    let (dx, dy) = (to.0 - from.0, to.1 - from.1);
+    let length = (dx * dx + dy * dy).sqrt();
+    if length < f32::EPSILON {
+        return;
+    }
+    let (ux, uy) = (dx / length, dy / length);
+
+    // 箭身：沿法线方向平移多条线段实现粗细
+    let half = thickness as f32 / 2.0;
+    let mut offset = -half;
+    while offset <= half {
+        let (ox, oy) = (-uy * offset, ux * offset);
+        draw_line_segment_mut(canvas, (from.0 + ox, from.1 + oy), (to.0 + ox, to.1 + oy), color);
+        offset += 1.0;
+    }
+
+    // 箭头：以终点为顶点的实心三角形
+    let head_length = (thickness as f32 * 4.0).max(10.0);
+    let head_width = head_length * 0.6;
+    let base = (to.0 - ux * head_length, to.1 - uy * head_length);
+    let head = [
+        Point::new(to.0.round() as i32, to.1.round() as i32),
+        Point::new((base.0 - uy * head_width).round() as i32, (base.1 + ux * head_width).round() as i32),
+        Point::new((base.0 + uy * head_width).round() as i32, (base.1 - ux * head_width).round() as i32),
+    ];
+    draw_polygon_mut(canvas, &head, color);
+}
+
+/// 内部函数：应用单个编辑操作
+fn apply_edit(img: DynamicImage, edit: &ImageEdit) -> AppResult<DynamicImage> {
+    let edited = match edit {
+        ImageEdit::Crop { x, y, width, height } => {
+            check_region(&img, *x, *y, *width, *height)?;
+            img.crop_imm(*x, *y, *width, *height)
+        }
+        ImageEdit::Resize { width, height, filter, keep_aspect } => {
+            if *width == 0 || *height == 0 {
+                return Err(AppError::InvalidInput("Resize target must not be empty".to_string()));
+            }
+            if *width > MAX_RESIZE_SIDE || *height > MAX_RESIZE_SIDE {
+                return Err(AppError::InvalidInput(format!(
+                    "Resize target {}x{} exceeds the maximum of {} px per side",
+                    width, height, MAX_RESIZE_SIDE
+                )));
+            }
+            if *keep_aspect {
+                img.resize(*width, *height, (*filter).into())
+            } else {
+                img.resize_exact(*width, *height, (*filter).into())
+            }
+        }
+        ImageEdit::Rotate { degrees } => match degrees % 360 {
+            0 => img,
+            90 => img.rotate90(),
+            180 => img.rotate180(),
+            270 => img.rotate270(),
+            _ => return Err(AppError::InvalidInput(format!("Unsupported rotation: {} degrees", degrees))),
+        },
+        ImageEdit::Flip { direction } => match direction {
+            FlipDirection::Horizontal => img.fliph(),
+            FlipDirection::Vertical => img.flipv(),
+        },
+        ImageEdit::Blur { x, y, width, height, sigma } => {
+            check_region(&img, *x, *y, *width, *height)?;
+            let region = img.crop_imm(*x, *y, *width, *height).blur(sigma.unwrap_or(8.0));
+            let mut canvas = img;
+            imageops::overlay(&mut canvas, &region, *x as i64, *y as i64);
+            canvas
+        }
+        ImageEdit::Rectangle { x, y, width, height, color, thickness } => {
+            check_region(&img, *x, *y, *width, *height)?;
+            let thickness = check_thickness(*thickness)?;
+            let color = parse_color(color.as_deref())?;
+            let mut canvas = img.to_rgba8();
+            for i in 0..thickness {
+                let inset = i.saturating_mul(2);
+                if *width <= inset || *height <= inset {
+                    // 粗细超过矩形一半时直接填充
+                    let rect = Rect::at(*x as i32, *y as i32).of_size(*width, *height);
+                    draw_filled_rect_mut(&mut canvas, rect, color);
+                    break;
+                }
+                let rect = Rect::at(x.saturating_add(i) as i32, y.saturating_add(i) as i32)
+                    .of_size(width - inset, height - inset);
+                draw_hollow_rect_mut(&mut canvas, rect, color);
+            }
+            DynamicImage::ImageRgba8(canvas)
+        }
+        ImageEdit::Arrow { from_x, from_y, to_x, to_y, color, thickness } => {
+            let thickness = check_thickness(*thickness)?;
+            let color = parse_color(color.as_deref())?;
+            let mut canvas = img.to_rgba8();
+            let from = clip_point(&canvas, *from_x, *from_y)?;
+            let to = clip_point(&canvas, *to_x, *to_y)?;
+            draw_arrow(&mut canvas, from, to, color, thickness);
+            DynamicImage::ImageRgba8(canvas)
+        }
+    };
+
+    Ok(edited)
+}
+
+/// 编辑图片历史记录
+///
+/// 按顺序应用裁剪、缩放、旋转、翻转、打码和标注操作，
+/// 结果保存为新的图片记录（parentId 指向原图），原图保持不变
+#[tauri::command]
+pub async fn edit_image_item(
+    app: AppHandle,
+    id: String,
+    edits: Vec<ImageEdit>,
+) -> AppResult<ImageItem> {
+    if edits.is_empty() {
+        return Err(AppError::InvalidInput("No edits given".to_string()));
+    }
+
+    let pool = db::pool(&app).await?;
+    let content: String = sqlx::query_scalar("SELECT content FROM ImageItems WHERE id = ?")
+        .bind(&id)
+        .fetch_optional(&pool)
+        .await
+        .map_err(|e| AppError::Database(format!("Failed to query image: {}", e)))?
+        .ok_or_else(|| AppError::NotFound(format!("Image not found: {}", id)))?;
+
+    // 在后台线程中执行CPU密集型任务
+    let encoding = ImageEncoding::load(&app);
+    let encoded = tokio::task::spawn_blocking(move || {
+        let bytes = decode_data_url(&content)?;
+        let mut img = image::load_from_memory(&bytes)
+            .map_err(|e| AppError::Image(format!("Image decoding failed: {}", e)))?;
+
+        for edit in &edits {
+            img = apply_edit(img, edit)?;
+        }
+
+        encode_image(img, &encoding)
+    })
+    .await??;
+
+    db::insert_image_item(&pool, &encoded, Some(&id)).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn blank(width: u32, height: u32) -> DynamicImage {
+        DynamicImage::ImageRgba8(RgbaImage::from_pixel(width, height, Rgba([255, 255, 255, 255])))
+    }
+
+    #[test]
+    fn rejects_oversized_resize() {
+        let edit = ImageEdit::Resize {
+            width: 100_000,
+            height: 100_000,
+            filter: ResizeFilter::default(),
+            keep_aspect: false,
+        };
+        assert!(matches!(apply_edit(blank(4, 4), &edit), Err(AppError::InvalidInput(_))));
+    }
+
+    #[test]
+    fn rejects_oversized_thickness() {
+        let edit = ImageEdit::Rectangle {
+            x: 0,
+            y: 0,
+            width: 4,
+            height: 4,
+            color: None,
+            thickness: Some(MAX_THICKNESS + 1),
+        };
+        assert!(matches!(apply_edit(blank(4, 4), &edit), Err(AppError::InvalidInput(_))));
+    }
+
+    #[test]
+    fn rejects_rectangle_outside_image() {
+        let edit = ImageEdit::Rectangle {
+            x: u32::MAX,
+            y: 0,
+            width: 4,
+            height: 4,
+            color: None,
+            thickness: None,
+        };
+        assert!(matches!(apply_edit(blank(4, 4), &edit), Err(AppError::InvalidInput(_))));
+    }
+
+    #[test]
+    fn clips_arrow_to_canvas() {
+        let edit = ImageEdit::Arrow {
+            from_x: 0.0,
+            from_y: 0.0,
+            to_x: 1e30,
+            to_y: 1e30,
+            color: Some("#000000".to_string()),
+            thickness: Some(1),
+        };
+        // 终点被限制在画布内，绘制很快完成
+        let edited = apply_edit(blank(8, 8), &edit).unwrap().to_rgba8();
+        assert!(edited.pixels().any(|pixel| *pixel == Rgba([0, 0, 0, 255])));
+
+        let invalid = ImageEdit::Arrow {
+            from_x: f32::NAN,
+            from_y: 0.0,
+            to_x: 1.0,
+            to_y: 1.0,
+            color: None,
+            thickness: None,
+        };
+        assert!(matches!(apply_edit(blank(8, 8), &invalid), Err(AppError::InvalidInput(_))));
+    }
+}
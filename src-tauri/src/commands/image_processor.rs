@@ -1,17 +1,230 @@
-use image::{DynamicImage, ImageBuffer, ImageFormat, Rgba};
+use image::codecs::jpeg::JpegEncoder;
+use image::codecs::png::{CompressionType, FilterType as PngFilterType, PngEncoder};
+use image::imageops::{self, FilterType};
+use image::{ColorType, DynamicImage, ImageBuffer, ImageEncoder, Rgba, RgbaImage};
 use base64::{Engine as _, engine::general_purpose};
 use serde::{Deserialize, Serialize};
-use std::io::Cursor;
 use tauri::AppHandle;
 use tauri_plugin_clipboard_manager::ClipboardExt;
 use std::sync::Mutex;
 use lazy_static::lazy_static;
 
+use crate::config::AppConfig;
+use crate::error::{AppError, AppResult};
+
 // 使用lazy_static来保存上一次的图片hash
 lazy_static! {
     static ref LAST_IMAGE_HASH: Mutex<String> = Mutex::new(String::new());
 }
 
+/// 解码 data URL（data:image/png;base64,...）为原始图片字节
+pub(crate) fn decode_data_url(data_url: &str) -> AppResult<Vec<u8>> {
+    let base64_str = data_url
+        .split_once("base64,")
+        .map_or(data_url, |(_, data)| data);
+
+    general_purpose::STANDARD
+        .decode(base64_str)
+        .map_err(|e| AppError::Image(format!("Base64 decoding failed: {}", e)))
+}
+
+/// 编码图片字节为 data URL
+pub(crate) fn encode_data_url(bytes: &[u8], mime: &str) -> String {
+    format!("data:{};base64,{}", mime, general_purpose::STANDARD.encode(bytes))
+}
+
+/// 图片保存格式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImageOutputFormat {
+    /// PNG（无损）
+    #[default]
+    Png,
+    /// WebP 无损
+    WebpLossless,
+    /// WebP 有损
+    WebpLossy,
+    /// JPEG（有损，不支持透明）
+    Jpeg,
+}
+
+impl ImageOutputFormat {
+    /// 保存到数据库的格式名称
+    pub fn name(self) -> &'static str {
+        match self {
+            ImageOutputFormat::Png => "png",
+            ImageOutputFormat::WebpLossless | ImageOutputFormat::WebpLossy => "webp",
+            ImageOutputFormat::Jpeg => "jpeg",
+        }
+    }
+
+    fn mime(self) -> &'static str {
+        match self {
+            ImageOutputFormat::Png => "image/png",
+            ImageOutputFormat::WebpLossless | ImageOutputFormat::WebpLossy => "image/webp",
+            ImageOutputFormat::Jpeg => "image/jpeg",
+        }
+    }
+}
+
+/// PNG 压缩级别
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PngCompression {
+    Fast,
+    #[default]
+    Default,
+    Best,
+}
+
+impl From<PngCompression> for CompressionType {
+    fn from(compression: PngCompression) -> Self {
+        match compression {
+            PngCompression::Fast => CompressionType::Fast,
+            PngCompression::Default => CompressionType::Default,
+            PngCompression::Best => CompressionType::Best,
+        }
+    }
+}
+
+/// 图片编码参数（来自配置）
+#[derive(Debug, Clone, Copy)]
+pub struct ImageEncoding {
+    pub format: ImageOutputFormat,
+    /// 有损压缩质量（1-100）
+    pub quality: u8,
+    /// 最大宽度，0 表示不限制
+    pub max_width: u32,
+    /// 最大高度，0 表示不限制
+    pub max_height: u32,
+    pub png_compression: PngCompression,
+    /// 是否把透明图片合成到背景色上
+    pub flatten_alpha: bool,
+    /// 合成透明图片使用的背景色（JPEG 始终使用）
+    pub background: Rgba<u8>,
+}
+
+impl ImageEncoding {
+    pub fn from_config(config: &AppConfig) -> Self {
+        Self {
+            format: config.image_format,
+            quality: config.image_quality.clamp(1, 100),
+            max_width: config.image_max_width,
+            max_height: config.image_max_height,
+            png_compression: config.png_compression,
+            flatten_alpha: config.image_flatten_alpha,
+            background: parse_hex_color(&config.image_background_color)
+                .unwrap_or(Rgba([255, 255, 255, 255])),
+        }
+    }
+
+    /// 读取当前配置，读取失败时使用默认配置
+    pub fn load(app: &AppHandle) -> Self {
+        Self::from_config(&AppConfig::current(app).unwrap_or_default())
+    }
+}
+
+/// 解析 #RRGGBB 或 #RRGGBBAA 颜色（# 可省略）
+pub(crate) fn parse_hex_color(color: &str) -> AppResult<Rgba<u8>> {
+    let hex = color.trim_start_matches('#');
+    if (hex.len() != 6 && hex.len() != 8) || !hex.is_ascii() {
+        return Err(AppError::InvalidInput(format!("Invalid color: {}", color)));
+    }
+
+    let channel = |i: usize| {
+        u8::from_str_radix(&hex[i..i + 2], 16)
+            .map_err(|e| AppError::InvalidInput(format!("Invalid color {}: {}", color, e)))
+    };
+    let alpha = if hex.len() == 8 { channel(6)? } else { 255 };
+
+    Ok(Rgba([channel(0)?, channel(2)?, channel(4)?, alpha]))
+}
+
+/// 判断Alpha通道是否损坏
+///
+/// 部分剪切板来源（Tauri读取的图片）Alpha通道全部为0，
+/// 这种情况下图片实际是不透明的；只要有一个像素Alpha不为0，就认为是真实的透明通道
+pub(crate) fn is_alpha_broken(rgba_data: &[u8]) -> bool {
+    rgba_data.iter().skip(3).step_by(4).all(|&alpha| alpha == 0)
+}
+
+/// 把图片合成到不透明的背景色上
+pub(crate) fn flatten_alpha(img: &DynamicImage, background: Rgba<u8>) -> RgbaImage {
+    let Rgba([r, g, b, _]) = background;
+    let mut canvas = RgbaImage::from_pixel(img.width(), img.height(), Rgba([r, g, b, 255]));
+    imageops::overlay(&mut canvas, &img.to_rgba8(), 0, 0);
+    // 混合计算存在舍入误差，确保结果完全不透明
+    for pixel in canvas.pixels_mut() {
+        pixel.0[3] = 255;
+    }
+    canvas
+}
+
+/// 编码后的图片
+#[derive(Debug)]
+pub struct EncodedImage {
+    pub data_url: String,
+    pub width: u32,
+    pub height: u32,
+    pub format: ImageOutputFormat,
+    pub byte_size: usize,
+}
+
+/// 按编码参数缩放并编码图片
+///
+/// 超过最大宽高时等比缩小，然后按配置的格式编码为 data URL
+pub(crate) fn encode_image(img: DynamicImage, encoding: &ImageEncoding) -> AppResult<EncodedImage> {
+    let max_width = if encoding.max_width == 0 { u32::MAX } else { encoding.max_width };
+    let max_height = if encoding.max_height == 0 { u32::MAX } else { encoding.max_height };
+
+    let img = if img.width() > max_width || img.height() > max_height {
+        img.resize(max_width.min(img.width()), max_height.min(img.height()), FilterType::Triangle)
+    } else {
+        img
+    };
+
+    // JPEG 不支持透明，始终合成到背景色上
+    let img = if encoding.flatten_alpha || encoding.format == ImageOutputFormat::Jpeg {
+        DynamicImage::ImageRgba8(flatten_alpha(&img, encoding.background))
+    } else {
+        img
+    };
+    let (width, height) = (img.width(), img.height());
+
+    let mut buffer = Vec::new();
+    match encoding.format {
+        ImageOutputFormat::Png => {
+            PngEncoder::new_with_quality(&mut buffer, encoding.png_compression.into(), PngFilterType::Adaptive)
+                .write_image(img.to_rgba8().as_raw(), width, height, ColorType::Rgba8)
+                .map_err(|e| AppError::Image(format!("PNG encoding failed: {}", e)))?;
+        }
+        ImageOutputFormat::Jpeg => {
+            JpegEncoder::new_with_quality(&mut buffer, encoding.quality)
+                .encode(img.to_rgb8().as_raw(), width, height, ColorType::Rgb8)
+                .map_err(|e| AppError::Image(format!("JPEG encoding failed: {}", e)))?;
+        }
+        ImageOutputFormat::WebpLossless | ImageOutputFormat::WebpLossy => {
+            let rgba = img.to_rgba8();
+            let encoder = webp::Encoder::from_rgba(rgba.as_raw(), width, height);
+            // encode / encode_lossless 在编码失败（如超过 16383 像素）时会 panic
+            let lossless = encoding.format == ImageOutputFormat::WebpLossless;
+            let quality = if lossless { 75.0 } else { encoding.quality as f32 };
+            let memory = encoder
+                .encode_simple(lossless, quality)
+                .map_err(|e| AppError::Image(format!("WebP encoding failed: {:?}", e)))?;
+            buffer.extend_from_slice(&memory);
+        }
+    }
+
+    Ok(EncodedImage {
+        data_url: encode_data_url(&buffer, encoding.format.mime()),
+        width,
+        height,
+        format: encoding.format,
+        byte_size: buffer.len(),
+    })
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ProcessedImage {
     pub base64_data: String,
@@ -19,56 +232,23 @@ pub struct ProcessedImage {
     pub height: u32,
     pub original_width: u32,
     pub original_height: u32,
+    /// 编码格式（png / webp / jpeg）
+    pub format: String,
+    /// 编码后的字节数
+    pub byte_size: usize,
 }
 
-/// 处理剪切板图片：无损压缩、转base64，保持原始尺寸
+/// 处理剪切板图片：按配置缩放、压缩、转base64
 /// 
 /// 这个函数在独立线程中运行，不阻塞主线程
 #[tauri::command]
 pub async fn process_clipboard_image(
+    app: AppHandle,
     rgba_data: Vec<u8>,
     width: u32,
     height: u32,
-) -> Result<ProcessedImage, String> {
-    // 在后台线程中执行CPU密集型任务
-    tokio::task::spawn_blocking(move || {
-        // 1. 修复Alpha通道（Tauri读取的图片Alpha通道可能为0）
-        let mut fixed_rgba = rgba_data;
-        for i in (3..fixed_rgba.len()).step_by(4) {
-            fixed_rgba[i] = 255; // 设置为完全不透明
-        }
-        
-        // 2. 创建图片缓冲区
-        let img_buffer = ImageBuffer::<Rgba<u8>, Vec<u8>>::from_raw(
-            width,
-            height,
-            fixed_rgba,
-        )
-        .ok_or("Failed to create image buffer")?;
-        
-        let img = DynamicImage::ImageRgba8(img_buffer);
-        
-        // 3. 转换为PNG格式（无损压缩）
-        let mut png_buffer = Vec::new();
-        let mut cursor = Cursor::new(&mut png_buffer);
-        
-        img.write_to(&mut cursor, ImageFormat::Png)
-            .map_err(|e| format!("PNG encoding failed: {}", e))?;
-        
-        // 4. 转换为base64
-        let base64_str = general_purpose::STANDARD.encode(&png_buffer);
-        let data_url = format!("data:image/png;base64,{}", base64_str);
-        
-        Ok(ProcessedImage {
-            base64_data: data_url,
-            width,
-            height,
-            original_width: width,
-            original_height: height,
-        })
-    })
-    .await
-    .map_err(|e| format!("Task join error: {}", e))?
+) -> AppResult<ProcessedImage> {
+    process_image_internal(rgba_data, width, height, ImageEncoding::load(&app)).await
 }
 
 /// 计算图片内容的简单hash，用于去重
@@ -79,9 +259,9 @@ pub fn calculate_image_hash(
     rgba_data: Vec<u8>,
     width: u32,
     height: u32,
-) -> Result<String, String> {
+) -> AppResult<String> {
     if rgba_data.is_empty() {
-        return Err("Empty RGBA data".to_string());
+        return Err(AppError::InvalidInput("Empty RGBA data".to_string()));
     }
     
     // 基础hash：尺寸信息
@@ -126,7 +306,7 @@ pub fn calculate_image_hash(
 #[tauri::command]
 pub async fn monitor_and_process_clipboard_image(
     app: AppHandle,
-) -> Result<Option<ProcessedImageWithSize>, String> {
+) -> AppResult<Option<ProcessedImageWithSize>> {
     // 1. 从剪切板读取图片数据
     let clipboard_result = app.clipboard().read_image();
     
@@ -156,7 +336,7 @@ pub async fn monitor_and_process_clipboard_image(
     // 检查是否与上次的图片相同
     {
         let mut last_hash = LAST_IMAGE_HASH.lock()
-            .map_err(|e| format!("Failed to lock hash mutex: {}", e))?;
+            .map_err(|e| AppError::Internal(format!("Failed to lock hash mutex: {}", e)))?;
         
         if *last_hash == current_hash {
             // 图片没有变化，不需要处理
@@ -168,7 +348,17 @@ pub async fn monitor_and_process_clipboard_image(
     }
     
     // 3. 处理图片（缩放、压缩、转base64）
-    let processed = process_image_internal(rgba_bytes.to_vec(), width, height).await?;
+    let encoding = ImageEncoding::load(&app);
+    let processed = process_image_internal(rgba_bytes.to_vec(), width, height, encoding)
+        .await
+        .inspect_err(|e| tracing::error!("Failed to process clipboard image ({}x{}): {}", width, height, e))?;
+    tracing::debug!(
+        "Captured clipboard image {}x{} as {} ({} bytes)",
+        width,
+        height,
+        processed.format,
+        processed.byte_size
+    );
     
     // 4. 返回处理后的图片数据和原始大小
     Ok(Some(ProcessedImageWithSize {
@@ -176,6 +366,8 @@ pub async fn monitor_and_process_clipboard_image(
         width: processed.width,
         height: processed.height,
         original_size: (width * height * 4) as usize,
+        format: processed.format,
+        byte_size: processed.byte_size,
     }))
 }
 
@@ -185,6 +377,10 @@ pub struct ProcessedImageWithSize {
     pub width: u32,
     pub height: u32,
     pub original_size: usize,
+    /// 编码格式（png / webp / jpeg）
+    pub format: String,
+    /// 编码后的字节数
+    pub byte_size: usize,
 }
 
 /// 内部函数：计算图片hash（不需要异步）
@@ -192,9 +388,9 @@ fn calculate_image_hash_internal(
     rgba_data: &[u8],
     width: u32,
     height: u32,
-) -> Result<String, String> {
+) -> AppResult<String> {
     if rgba_data.is_empty() {
-        return Err("Empty RGBA data".to_string());
+        return Err(AppError::InvalidInput("Empty RGBA data".to_string()));
     }
     
     // 基础hash：尺寸信息
@@ -229,17 +425,20 @@ fn calculate_image_hash_internal(
     Ok(hash.to_string())
 }
 
-/// 内部函数：处理图片（无损压缩、转base64，保持原始尺寸）
+/// 内部函数：处理图片（按配置缩放、编码、转base64）
 async fn process_image_internal(
     rgba_data: Vec<u8>,
     width: u32,
     height: u32,
-) -> Result<ProcessedImage, String> {
+    encoding: ImageEncoding,
+) -> AppResult<ProcessedImage> {
     tokio::task::spawn_blocking(move || {
-        // 1. 修复Alpha通道（Tauri读取的图片Alpha通道可能为0）
+        // 1. 修复Alpha通道：仅当Alpha通道全部为0（来源损坏）时设置为不透明，保留真实的透明通道
         let mut fixed_rgba = rgba_data;
-        for i in (3..fixed_rgba.len()).step_by(4) {
-            fixed_rgba[i] = 255; // 设置为完全不透明
+        if is_alpha_broken(&fixed_rgba) {
+            for i in (3..fixed_rgba.len()).step_by(4) {
+                fixed_rgba[i] = 255; // 设置为完全不透明
+            }
         }
         
         // 2. 创建图片缓冲区
@@ -248,29 +447,118 @@ async fn process_image_internal(
             height,
             fixed_rgba,
         )
-        .ok_or("Failed to create image buffer")?;
+        .ok_or_else(|| AppError::Image("Failed to create image buffer".to_string()))?;
         
         let img = DynamicImage::ImageRgba8(img_buffer);
         
-        // 3. 转换为PNG格式（无损压缩）
-        let mut png_buffer = Vec::new();
-        let mut cursor = Cursor::new(&mut png_buffer);
-        
-        img.write_to(&mut cursor, ImageFormat::Png)
-            .map_err(|e| format!("PNG encoding failed: {}", e))?;
-        
-        // 4. 转换为base64
-        let base64_str = general_purpose::STANDARD.encode(&png_buffer);
-        let data_url = format!("data:image/png;base64,{}", base64_str);
+        // 3. 按配置缩放并编码，转换为base64
+        let encoded = encode_image(img, &encoding)?;
         
         Ok(ProcessedImage {
-            base64_data: data_url,
-            width,
-            height,
+            base64_data: encoded.data_url,
+            width: encoded.width,
+            height: encoded.height,
             original_width: width,
             original_height: height,
+            format: encoded.format.name().to_string(),
+            byte_size: encoded.byte_size,
         })
     })
-    .await
-    .map_err(|e| format!("Task join error: {}", e))?
+    .await?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encoding(format: ImageOutputFormat) -> ImageEncoding {
+        ImageEncoding {
+            format,
+            quality: 80,
+            max_width: 0,
+            max_height: 0,
+            png_compression: PngCompression::Default,
+            flatten_alpha: false,
+            background: Rgba([255, 255, 255, 255]),
+        }
+    }
+
+    /// 内部函数：解码 data URL 中的图片
+    fn decode(data_url: &str) -> RgbaImage {
+        let bytes = decode_data_url(data_url).unwrap();
+        if data_url.starts_with("data:image/webp") {
+            let image = webp::Decoder::new(&bytes).decode().unwrap();
+            let pixels = if image.is_alpha() {
+                image.to_vec()
+            } else {
+                image.chunks(3).flat_map(|p| [p[0], p[1], p[2], 255]).collect()
+            };
+            RgbaImage::from_raw(image.width(), image.height(), pixels).unwrap()
+        } else {
+            image::load_from_memory(&bytes).unwrap().to_rgba8()
+        }
+    }
+
+    #[test]
+    fn detects_broken_alpha() {
+        assert!(is_alpha_broken(&[10, 20, 30, 0, 40, 50, 60, 0]));
+        assert!(!is_alpha_broken(&[10, 20, 30, 0, 40, 50, 60, 128]));
+    }
+
+    #[tokio::test]
+    async fn broken_alpha_is_made_opaque() {
+        let rgba = vec![10, 20, 30, 0, 40, 50, 60, 0];
+        let processed = process_image_internal(rgba, 2, 1, encoding(ImageOutputFormat::Png)).await.unwrap();
+
+        let decoded = decode(&processed.base64_data);
+        assert_eq!(decoded.get_pixel(0, 0), &Rgba([10, 20, 30, 255]));
+        assert_eq!(decoded.get_pixel(1, 0), &Rgba([40, 50, 60, 255]));
+    }
+
+    #[tokio::test]
+    async fn partial_alpha_is_kept() {
+        let rgba = vec![10, 20, 30, 0, 40, 50, 60, 128];
+        let processed = process_image_internal(rgba, 2, 1, encoding(ImageOutputFormat::Png)).await.unwrap();
+
+        let decoded = decode(&processed.base64_data);
+        assert_eq!(decoded.get_pixel(0, 0).0[3], 0);
+        assert_eq!(decoded.get_pixel(1, 0), &Rgba([40, 50, 60, 128]));
+    }
+
+    #[test]
+    fn flattens_onto_background() {
+        let img = DynamicImage::ImageRgba8(RgbaImage::from_raw(2, 1, vec![10, 20, 30, 0, 40, 50, 60, 255]).unwrap());
+        let flattened = flatten_alpha(&img, Rgba([200, 100, 0, 255]));
+        assert_eq!(flattened.get_pixel(0, 0), &Rgba([200, 100, 0, 255]));
+        assert_eq!(flattened.get_pixel(1, 0), &Rgba([40, 50, 60, 255]));
+    }
+
+    #[test]
+    fn every_format_is_decodable() {
+        let img = DynamicImage::ImageRgba8(RgbaImage::from_pixel(16, 8, Rgba([0, 128, 255, 128])));
+        for format in [
+            ImageOutputFormat::Png,
+            ImageOutputFormat::WebpLossless,
+            ImageOutputFormat::WebpLossy,
+            ImageOutputFormat::Jpeg,
+        ] {
+            let encoded = encode_image(img.clone(), &encoding(format)).unwrap();
+            assert!(encoded.data_url.starts_with(&format!("data:{};base64,", format.mime())));
+
+            let decoded = decode(&encoded.data_url);
+            assert_eq!(decoded.dimensions(), (16, 8), "{:?}", format);
+            // JPEG 不支持透明，合成到背景色上
+            if format == ImageOutputFormat::Jpeg {
+                assert_eq!(decoded.get_pixel(0, 0).0[3], 255);
+            }
+        }
+    }
+
+    #[test]
+    fn large_images_are_scaled_down() {
+        let img = DynamicImage::ImageRgba8(RgbaImage::from_pixel(40, 20, Rgba([0, 0, 0, 255])));
+        let encoded = encode_image(img, &ImageEncoding { max_width: 10, ..encoding(ImageOutputFormat::Png) }).unwrap();
+        assert_eq!((encoded.width, encoded.height), (10, 5));
+        assert_eq!(decode(&encoded.data_url).dimensions(), (10, 5));
+    }
 }
@@ -0,0 +1,271 @@
+use serde::{Deserialize, Serialize};
+use sqlx::sqlite::SqliteConnection;
+use tauri::{AppHandle, Emitter};
+
+use crate::commands::tags::ItemType;
+use crate::config::AppConfig;
+use crate::db;
+use crate::error::{AppError, AppResult};
+
+/// 记录内容修改事件，载荷为 UpdatedItem
+pub const ITEM_UPDATED_EVENT: &str = "item-updated";
+
+/// 修改前的内容（ItemRevisions 表）
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+#[sqlx(rename_all = "camelCase")]
+pub struct ItemRevision {
+    pub id: String,
+    pub item_id: String,
+    pub item_type: String,
+    pub content: String,
+    pub create_time: String,
+}
+
+/// 内容已修改的记录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdatedItem {
+    pub id: String,
+    pub item_type: ItemType,
+    /// 新内容，压缩存储时为预览
+    pub content: String,
+    pub update_time: String,
+    /// 完整内容是否压缩存储
+    pub compressed: bool,
+}
+
+/// 内部函数：查询可编辑记录（文本历史或分组内容）的类型和当前内容
+async fn find_item(conn: &mut SqliteConnection, id: &str) -> AppResult<(ItemType, String)> {
+    let found: Option<(String, String, Option<Vec<u8>>)> = sqlx::query_as(
+        "SELECT 'cut_item', content, contentBlob FROM CutItems WHERE id = ?1 AND deletedAt IS NULL
+         UNION ALL
+         SELECT 'group_item', content, NULL FROM GroupItems WHERE id = ?1
+         LIMIT 1",
+    )
+    .bind(id)
+    .fetch_optional(&mut *conn)
+    .await
+    .map_err(|e| AppError::Database(format!("Failed to query item: {}", e)))?;
+
+    match found {
+        Some((item_type, content, blob)) if item_type == "cut_item" => {
+            Ok((ItemType::CutItem, db::full_content(content, blob)?))
+        }
+        Some((_, content, _)) => Ok((ItemType::GroupItem, content)),
+        None => Err(AppError::NotFound(format!("Item not found: {}", id))),
+    }
+}
+
+/// 内部函数：保存修改前的内容并更新记录，超出保留数量的旧版本会被删除
+///
+/// 同时更新 updateTime，多设备同步按它判断修改的先后；
+/// loaded_length 为编辑前读取到的内容字符数，与当前完整内容不一致时拒绝修改，
+/// 避免只读取到预览（压缩存储的记录）时用预览覆盖完整内容；
+/// 历史版本和文本历史一样，超过压缩阈值时压缩存储
+async fn save_content(
+    conn: &mut SqliteConnection,
+    id: &str,
+    content: &str,
+    loaded_length: usize,
+    config: &AppConfig,
+) -> AppResult<Option<UpdatedItem>> {
+    let (item_type, previous) = find_item(conn, id).await?;
+    if loaded_length != previous.chars().count() {
+        return Err(AppError::InvalidInput(
+            "Item content was not fully loaded or has changed, reload it before saving".to_string(),
+        ));
+    }
+    if previous == content {
+        return Ok(None);
+    }
+
+    let now = db::now_string();
+    let stored = db::StoredText::new(&previous, config.compress_threshold_bytes())?;
+    sqlx::query(
+        "INSERT INTO ItemRevisions (id, itemId, itemType, content, contentBlob, createTime) VALUES (?, ?, ?, ?, ?, ?)",
+    )
+    .bind(uuid::Uuid::new_v4().to_string())
+    .bind(id)
+    .bind(item_type.name())
+    .bind(&stored.content)
+    .bind(&stored.blob)
+    .bind(&now)
+    .execute(&mut *conn)
+    .await
+    .map_err(|e| AppError::Database(format!("Failed to save revision: {}", e)))?;
+
+    match item_type {
+        ItemType::CutItem => {
+            db::update_cut_item_content(conn, id, content, &now, config.compress_threshold_bytes()).await?;
+        }
+        _ => {
+            sqlx::query("UPDATE GroupItems SET content = ?, updateTime = ? WHERE id = ?")
+                .bind(content)
+                .bind(&now)
+                .bind(id)
+                .execute(&mut *conn)
+                .await
+                .map_err(|e| AppError::Database(format!("Failed to update item: {}", e)))?;
+        }
+    }
+
+    sqlx::query(
+        "DELETE FROM ItemRevisions WHERE id IN (
+            SELECT id FROM ItemRevisions WHERE itemId = ? ORDER BY createTime DESC LIMIT -1 OFFSET ?
+         )",
+    )
+    .bind(id)
+    .bind(config.max_revisions_per_item)
+    .execute(&mut *conn)
+    .await
+    .map_err(|e| AppError::Database(format!("Failed to trim revisions: {}", e)))?;
+
+    let compressed = item_type == ItemType::CutItem && content.len() > config.compress_threshold_bytes();
+    Ok(Some(UpdatedItem {
+        id: id.to_string(),
+        item_type,
+        content: if compressed { db::preview_of(content) } else { content.to_string() },
+        update_time: now,
+        compressed,
+    }))
+}
+
+/// 内部函数：在事务中修改内容并通知前端
+async fn update_content(app: &AppHandle, id: &str, content: &str, loaded_length: usize) -> AppResult<()> {
+    let config = AppConfig::current(app)?;
+    db::check_text_size(content, &config)?;
+    let pool = db::pool(app).await?;
+
+    let mut tx = pool.begin().await?;
+    let updated = save_content(&mut tx, id, content, loaded_length, &config).await?;
+    tx.commit().await?;
+
+    if let Some(updated) = updated {
+        app.emit(ITEM_UPDATED_EVENT, &updated)?;
+    }
+    Ok(())
+}
+
+/// 修改文本历史或分组内容，修改前的内容保存为历史版本
+///
+/// 内容没有变化时不做修改；loaded_length 为编辑前读取到的内容字符数，与当前内容不一致时返回错误
+#[tauri::command]
+pub async fn update_item_content(
+    app: AppHandle,
+    id: String,
+    content: String,
+    loaded_length: usize,
+) -> AppResult<()> {
+    update_content(&app, &id, &content, loaded_length).await
+}
+
+/// 内部函数：查询记录的历史版本（完整内容），按修改时间倒序
+async fn query_revisions(conn: &mut SqliteConnection, id: &str) -> AppResult<Vec<ItemRevision>> {
+    let rows: Vec<(String, String, String, String, Option<Vec<u8>>, String)> = sqlx::query_as(
+        "SELECT id, itemId, itemType, content, contentBlob, createTime FROM ItemRevisions
+         WHERE itemId = ? ORDER BY createTime DESC",
+    )
+    .bind(id)
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(|e| AppError::Database(format!("Failed to query revisions: {}", e)))?;
+
+    rows.into_iter()
+        .map(|(id, item_id, item_type, content, blob, create_time)| {
+            Ok(ItemRevision {
+                id,
+                item_id,
+                item_type,
+                content: db::full_content(content, blob)?,
+                create_time,
+            })
+        })
+        .collect()
+}
+
+/// 查询记录的历史版本，按修改时间倒序
+#[tauri::command]
+pub async fn list_revisions(app: AppHandle, id: String) -> AppResult<Vec<ItemRevision>> {
+    let pool = db::pool(&app).await?;
+    let mut conn = pool.acquire().await?;
+    query_revisions(&mut conn, &id).await
+}
+
+/// 把记录恢复为某个历史版本的内容，恢复前的内容同样保存为历史版本
+#[tauri::command]
+pub async fn restore_revision(app: AppHandle, revision_id: String) -> AppResult<()> {
+    let pool = db::pool(&app).await?;
+    let mut conn = pool.acquire().await?;
+    let (item_id, content, blob): (String, String, Option<Vec<u8>>) =
+        sqlx::query_as("SELECT itemId, content, contentBlob FROM ItemRevisions WHERE id = ?")
+            .bind(&revision_id)
+            .fetch_optional(&mut *conn)
+            .await
+            .map_err(|e| AppError::Database(format!("Failed to query revision: {}", e)))?
+            .ok_or_else(|| AppError::NotFound(format!("Revision not found: {}", revision_id)))?;
+    let content = db::full_content(content, blob)?;
+
+    // 恢复时以当前完整内容为准，不需要前端读取的长度
+    let (_, current) = find_item(&mut conn, &item_id).await?;
+    drop(conn);
+
+    update_content(&app, &item_id, &content, current.chars().count()).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::migrations;
+
+    #[tokio::test]
+    async fn revisions_are_compressed() {
+        let pool = migrations::migrated_pool().await;
+        let config = AppConfig {
+            compress_threshold_kb: 1,
+            ..AppConfig::default()
+        };
+        let original = "原始内容".repeat(200);
+        let item = db::insert_cut_item(&pool, &original, db::SELECTION_CLIPBOARD, config.compress_threshold_bytes())
+            .await
+            .unwrap();
+
+        let mut conn = pool.acquire().await.unwrap();
+        let loaded = original.chars().count();
+        save_content(&mut conn, &item.id, "新内容", loaded, &config).await.unwrap().unwrap();
+
+        let (content, blob): (String, Option<Vec<u8>>) =
+            sqlx::query_as("SELECT content, contentBlob FROM ItemRevisions WHERE itemId = ?")
+                .bind(&item.id)
+                .fetch_one(&mut *conn)
+                .await
+                .unwrap();
+        assert!(blob.is_some());
+        assert_eq!(content, db::preview_of(&original));
+
+        let revisions = query_revisions(&mut conn, &item.id).await.unwrap();
+        assert_eq!(revisions.len(), 1);
+        assert_eq!(revisions[0].content, original);
+    }
+
+    #[tokio::test]
+    async fn rejects_partially_loaded_content() {
+        let pool = migrations::migrated_pool().await;
+        let config = AppConfig {
+            compress_threshold_kb: 1,
+            ..AppConfig::default()
+        };
+        let original = "原始内容".repeat(200);
+        let item = db::insert_cut_item(&pool, &original, db::SELECTION_CLIPBOARD, config.compress_threshold_bytes())
+            .await
+            .unwrap();
+
+        // 只读取到预览时不能保存
+        let mut conn = pool.acquire().await.unwrap();
+        let preview = db::preview_of(&original);
+        let result = save_content(&mut conn, &item.id, "新内容", preview.chars().count(), &config).await;
+        assert!(matches!(result, Err(AppError::InvalidInput(_))));
+        drop(conn);
+        assert_eq!(db::get_item_content(&pool, &item.id).await.unwrap(), original);
+    }
+}
@@ -0,0 +1,266 @@
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use sqlx::sqlite::SqlitePool;
+use std::sync::Mutex;
+use tauri::AppHandle;
+
+use crate::config::AppConfig;
+use crate::db::{self, CutItem};
+use crate::error::{AppError, AppResult};
+use crate::utils::clipboard;
+
+// 收集模式缓冲区：None 表示未开启收集模式
+lazy_static! {
+    static ref COLLECT_BUFFER: Mutex<Option<Vec<String>>> = Mutex::new(None);
+}
+
+/// 合并顺序
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MergeOrder {
+    /// 按选择顺序
+    #[default]
+    Selection,
+    /// 按复制时间（从早到晚）
+    Time,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MergeResult {
+    /// 合并后的内容
+    pub content: String,
+    /// 保存为新记录时返回新记录
+    pub item: Option<CutItem>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CollectStatus {
+    pub active: bool,
+    pub count: usize,
+}
+
+fn default_separator(separator: Option<String>) -> String {
+    separator.unwrap_or_else(|| "\n".to_string())
+}
+
+/// 内部函数：写入剪切板，并按需保存为新记录（超过最大大小时不保存并返回错误）
+async fn write_merged(app: &AppHandle, content: String, save: bool) -> AppResult<MergeResult> {
+    let config = AppConfig::current(app)?;
+    if save {
+        db::check_text_size(&content, &config)?;
+    }
+    clipboard::write_text(app, &content)?;
+
+    let item = if save {
+        let pool = db::pool(app).await?;
+        Some(db::insert_cut_item(&pool, &content, db::SELECTION_CLIPBOARD, config.compress_threshold_bytes()).await?)
+    } else {
+        None
+    };
+
+    Ok(MergeResult { content, item })
+}
+
+/// 内部函数：按顺序拼接多条历史记录（不含回收站）的完整内容
+async fn merge_contents(pool: &SqlitePool, ids: &[String], separator: &str, order: MergeOrder) -> AppResult<String> {
+    if ids.is_empty() {
+        return Err(AppError::InvalidInput("No items selected".to_string()));
+    }
+
+    let placeholders = vec!["?"; ids.len()].join(", ");
+    let sql = format!(
+        "SELECT {} FROM CutItems WHERE deletedAt IS NULL AND id IN ({})",
+        db::CUT_ITEM_COLUMNS,
+        placeholders
+    );
+
+    let mut query = sqlx::query_as::<_, CutItem>(&sql);
+    for id in ids {
+        query = query.bind(id);
+    }
+    let mut items = query
+        .fetch_all(pool)
+        .await
+        .map_err(|e| AppError::Database(format!("Failed to query items: {}", e)))?;
+
+    // 不存在或已在回收站中的记录不能合并
+    if items.len() < ids.len() {
+        let missing: Vec<&str> = ids
+            .iter()
+            .filter(|id| !items.iter().any(|item| item.id == **id))
+            .map(String::as_str)
+            .collect();
+        if !missing.is_empty() {
+            return Err(AppError::NotFound(format!("Items not found: {}", missing.join(", "))));
+        }
+    }
+
+    match order {
+        MergeOrder::Selection => {
+            items.sort_by_key(|item| ids.iter().position(|id| *id == item.id));
+        }
+        MergeOrder::Time => {
+            items.sort_by(|a, b| a.create_time.cmp(&b.create_time));
+        }
+    }
+
+    // 压缩存储的记录需要读取完整内容
+    let mut contents = Vec::with_capacity(items.len());
+    for item in items {
+        let content = if item.compressed {
+            db::get_item_content(pool, &item.id).await?
+        } else {
+            item.content
+        };
+        contents.push(content);
+    }
+    Ok(contents.join(separator))
+}
+
+/// 合并多条历史记录为一条剪切板内容
+///
+/// 按选择顺序或复制时间拼接，写入剪切板，可选保存为新记录
+#[tauri::command]
+pub async fn merge_items(
+    app: AppHandle,
+    ids: Vec<String>,
+    separator: Option<String>,
+    order: Option<MergeOrder>,
+    save: Option<bool>,
+) -> AppResult<MergeResult> {
+    let pool = db::pool(&app).await?;
+    let content = merge_contents(&pool, &ids, &default_separator(separator), order.unwrap_or_default()).await?;
+
+    write_merged(&app, content, save.unwrap_or(false)).await
+}
+
+/// 开启收集模式：之后每次复制的内容都会追加到缓冲区
+#[tauri::command]
+pub fn start_collect() -> AppResult<()> {
+    let mut buffer = COLLECT_BUFFER
+        .lock()
+        .map_err(|e| AppError::Internal(format!("Failed to lock collect buffer: {}", e)))?;
+    *buffer = Some(Vec::new());
+    Ok(())
+}
+
+/// 追加一条复制内容到收集缓冲区（由剪切板监控调用）
+///
+/// 返回是否处于收集模式
+#[tauri::command]
+pub fn collect_append(content: String) -> AppResult<bool> {
+    let mut buffer = COLLECT_BUFFER
+        .lock()
+        .map_err(|e| AppError::Internal(format!("Failed to lock collect buffer: {}", e)))?;
+
+    match buffer.as_mut() {
+        Some(items) => {
+            items.push(content);
+            Ok(true)
+        }
+        None => Ok(false),
+    }
+}
+
+/// 结束收集模式：把缓冲区内容合并写入剪切板，可选保存为新记录
+#[tauri::command]
+pub async fn finish_collect(
+    app: AppHandle,
+    separator: Option<String>,
+    save: Option<bool>,
+) -> AppResult<Option<MergeResult>> {
+    let items = COLLECT_BUFFER
+        .lock()
+        .map_err(|e| AppError::Internal(format!("Failed to lock collect buffer: {}", e)))?
+        .take()
+        .unwrap_or_default();
+
+    if items.is_empty() {
+        return Ok(None);
+    }
+
+    let content = items.join(&default_separator(separator));
+    write_merged(&app, content, save.unwrap_or(false)).await.map(Some)
+}
+
+/// 取消收集模式，丢弃缓冲区内容
+#[tauri::command]
+pub fn cancel_collect() -> AppResult<()> {
+    COLLECT_BUFFER
+        .lock()
+        .map_err(|e| AppError::Internal(format!("Failed to lock collect buffer: {}", e)))?
+        .take();
+    Ok(())
+}
+
+/// 获取收集模式状态
+#[tauri::command]
+pub fn get_collect_status() -> AppResult<CollectStatus> {
+    let buffer = COLLECT_BUFFER
+        .lock()
+        .map_err(|e| AppError::Internal(format!("Failed to lock collect buffer: {}", e)))?;
+
+    Ok(CollectStatus {
+        active: buffer.is_some(),
+        count: buffer.as_ref().map_or(0, |items| items.len()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::migrations;
+
+    /// 内部函数：按指定创建时间添加记录，返回记录 ID
+    async fn add(pool: &SqlitePool, content: &str, create_time: &str, compress_threshold: usize) -> String {
+        let item = db::insert_cut_item(pool, content, db::SELECTION_CLIPBOARD, compress_threshold).await.unwrap();
+        sqlx::query("UPDATE CutItems SET createTime = ? WHERE id = ?")
+            .bind(create_time)
+            .bind(&item.id)
+            .execute(pool)
+            .await
+            .unwrap();
+        item.id
+    }
+
+    #[tokio::test]
+    async fn merges_in_selection_and_time_order() {
+        let pool = migrations::migrated_pool().await;
+        let first = add(&pool, "第一条", "2024-01-01T00:00:00.000Z", usize::MAX).await;
+        let second = add(&pool, "第二条", "2024-01-02T00:00:00.000Z", usize::MAX).await;
+        let ids = vec![second, first];
+
+        let separator = default_separator(None);
+        let merged = merge_contents(&pool, &ids, &separator, MergeOrder::Selection).await.unwrap();
+        assert_eq!(merged, "第二条\n第一条");
+
+        let merged = merge_contents(&pool, &ids, " | ", MergeOrder::Time).await.unwrap();
+        assert_eq!(merged, "第一条 | 第二条");
+    }
+
+    #[tokio::test]
+    async fn merges_full_content_of_compressed_items() {
+        let pool = migrations::migrated_pool().await;
+        let long = "长文本".repeat(500);
+        let ids = vec![
+            add(&pool, &long, "2024-01-01T00:00:00.000Z", 1024).await,
+            add(&pool, "短文本", "2024-01-02T00:00:00.000Z", 1024).await,
+        ];
+
+        let merged = merge_contents(&pool, &ids, "", MergeOrder::Selection).await.unwrap();
+        assert_eq!(merged, format!("{}短文本", long));
+    }
+
+    #[tokio::test]
+    async fn rejects_missing_and_trashed_items() {
+        let pool = migrations::migrated_pool().await;
+        let kept = add(&pool, "保留", "2024-01-01T00:00:00.000Z", usize::MAX).await;
+        let trashed = add(&pool, "删除", "2024-01-02T00:00:00.000Z", usize::MAX).await;
+        db::trash_item(&pool, &trashed).await.unwrap();
+
+        let result = merge_contents(&pool, &[kept, trashed], "\n", MergeOrder::Selection).await;
+        assert!(matches!(result, Err(AppError::NotFound(_))));
+        let result = merge_contents(&pool, &[], "\n", MergeOrder::Selection).await;
+        assert!(matches!(result, Err(AppError::InvalidInput(_))));
+    }
+}
@@ -1,2 +1,9 @@
 pub mod cut_admin;
+pub mod db_maintenance;
+pub mod image_editor;
 pub mod image_processor;
+pub mod item_edit;
+pub mod item_merge;
+pub mod paste;
+pub mod ocr;
+pub mod tags;
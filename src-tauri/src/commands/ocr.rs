@@ -0,0 +1,115 @@
+use tauri::AppHandle;
+
+use crate::commands::image_processor::decode_data_url;
+use crate::commands::tags::{self, ItemType};
+use crate::config::AppConfig;
+use crate::db::{self, ImageItem};
+use crate::error::{AppError, AppResult};
+use crate::utils::clipboard;
+
+/// 内部函数：使用 Tesseract 识别图片中的文字
+#[cfg(feature = "ocr")]
+fn recognize(image_bytes: &[u8], language: &str, data_path: Option<&str>) -> AppResult<String> {
+    let mut tesseract = tesseract::Tesseract::new(data_path, Some(language))
+        .map_err(|e| AppError::Ocr(format!("Failed to initialize OCR engine: {}", e)))?
+        .set_image_from_mem(image_bytes)
+        .map_err(|e| AppError::Ocr(format!("Failed to load image for OCR: {}", e)))?;
+
+    let text = tesseract
+        .get_text()
+        .map_err(|e| AppError::Ocr(format!("OCR recognition failed: {}", e)))?;
+
+    Ok(text.trim().to_string())
+}
+
+#[cfg(not(feature = "ocr"))]
+fn recognize(_image_bytes: &[u8], _language: &str, _data_path: Option<&str>) -> AppResult<String> {
+    Err(AppError::Ocr("OCR is not available in this build (enable the `ocr` feature)".to_string()))
+}
+
+/// 内部函数：识别指定图片记录的文字并保存到 ocrText 列
+async fn recognize_image_item(app: &AppHandle, id: &str, config: &AppConfig) -> AppResult<String> {
+    let pool = db::pool(app).await?;
+    let content: String = sqlx::query_scalar("SELECT content FROM ImageItems WHERE id = ?")
+        .bind(id)
+        .fetch_optional(&pool)
+        .await
+        .map_err(|e| AppError::Database(format!("Failed to query image: {}", e)))?
+        .ok_or_else(|| AppError::NotFound(format!("Image not found: {}", id)))?;
+
+    let language = config.ocr_language.clone();
+    let data_path = config.ocr_data_path.clone();
+
+    // OCR 是CPU密集型任务，放到后台线程执行
+    let text = tokio::task::spawn_blocking(move || {
+        let image_bytes = decode_data_url(&content)?;
+        recognize(&image_bytes, &language, data_path.as_deref())
+    })
+    .await??;
+
+    sqlx::query("UPDATE ImageItems SET ocrText = ? WHERE id = ?")
+        .bind(&text)
+        .bind(id)
+        .execute(&pool)
+        .await
+        .map_err(|e| AppError::Database(format!("Failed to save OCR text: {}", e)))?;
+
+    Ok(text)
+}
+
+/// 识别新保存的图片中的文字（图片保存后由前端调用），并按识别出的文字应用自动标签规则
+///
+/// 未开启OCR时直接返回 None
+#[tauri::command]
+pub async fn ocr_image_item(app: AppHandle, id: String) -> AppResult<Option<String>> {
+    let config = AppConfig::current(&app)?;
+    if !config.ocr_enabled {
+        return Ok(None);
+    }
+
+    let text = recognize_image_item(&app, &id, &config).await?;
+    let pool = db::pool(&app).await?;
+    if let Err(e) = tags::apply_auto_tags(&pool, &id, ItemType::ImageItem, &text, &config.auto_tag_rules).await {
+        tracing::error!("Failed to apply auto tags: {}", e);
+    }
+    Ok(Some(text))
+}
+
+/// 提取图片中的文字并复制到剪切板
+///
+/// 已识别过的图片直接使用保存的结果
+#[tauri::command]
+pub async fn extract_text_from_image(app: AppHandle, id: String) -> AppResult<String> {
+    let pool = db::pool(&app).await?;
+    let ocr_text: Option<String> = sqlx::query_scalar("SELECT ocrText FROM ImageItems WHERE id = ?")
+        .bind(&id)
+        .fetch_optional(&pool)
+        .await
+        .map_err(|e| AppError::Database(format!("Failed to query image: {}", e)))?
+        .ok_or_else(|| AppError::NotFound(format!("Image not found: {}", id)))?;
+
+    let text = match ocr_text {
+        Some(text) => text,
+        None => {
+            let config = AppConfig::current(&app)?;
+            recognize_image_item(&app, &id, &config).await?
+        }
+    };
+
+    clipboard::write_text(&app, &text)?;
+    Ok(text)
+}
+
+/// 按识别出的文字搜索图片历史
+#[tauri::command]
+pub async fn search_image_items(app: AppHandle, keyword: String) -> AppResult<Vec<ImageItem>> {
+    let pool = db::pool(&app).await?;
+
+    sqlx::query_as::<_, ImageItem>(
+        "SELECT * FROM ImageItems WHERE deletedAt IS NULL AND ocrText LIKE ? ORDER BY createTime DESC",
+    )
+    .bind(format!("%{}%", keyword))
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| AppError::Database(format!("Failed to search images: {}", e)))
+}
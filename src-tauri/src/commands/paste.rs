@@ -0,0 +1,185 @@
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tauri::AppHandle;
+use tauri_plugin_clipboard_manager::ClipboardExt;
+
+use crate::config::AppConfig;
+use crate::db;
+use crate::error::{AppError, AppResult};
+use crate::utils::clipboard;
+
+/// 粘贴模式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PasteMode {
+    /// 纯文本（丢弃富文本格式）
+    #[default]
+    Plain,
+    /// 纯文本，合并连续空白为单个空格
+    CollapseWhitespace,
+    /// 纯文本，换行符统一为 LF
+    Lf,
+    /// 纯文本，换行符统一为 CRLF
+    Crlf,
+}
+
+/// 按粘贴模式转换文本
+pub fn transform_text(text: &str, mode: PasteMode) -> String {
+    match mode {
+        PasteMode::Plain => text.to_string(),
+        PasteMode::CollapseWhitespace => text.split_whitespace().collect::<Vec<_>>().join(" "),
+        PasteMode::Lf => text.replace("\r\n", "\n").replace('\r', "\n"),
+        PasteMode::Crlf => transform_text(text, PasteMode::Lf).replace('\n', "\r\n"),
+    }
+}
+
+/// 内部函数：隐藏主窗口，把焦点还给目标应用后模拟粘贴快捷键
+async fn simulate_paste(app: &AppHandle) -> AppResult<()> {
+    if let Err(e) = crate::window_manager::hide_main(app) {
+        tracing::warn!("Failed to hide main window before pasting: {}", e);
+    }
+
+    tokio::task::spawn_blocking(|| {
+        use enigo::{Direction, Enigo, Key, Keyboard, Settings};
+
+        // 等待焦点切换回目标应用
+        std::thread::sleep(Duration::from_millis(150));
+
+        let mut enigo = Enigo::new(&Settings::default())
+            .map_err(|e| AppError::System(format!("Failed to create input simulator: {}", e)))?;
+
+        #[cfg(target_os = "macos")]
+        let modifier = Key::Meta;
+        #[cfg(not(target_os = "macos"))]
+        let modifier = Key::Control;
+
+        enigo
+            .key(modifier, Direction::Press)
+            .and_then(|_| enigo.key(Key::Unicode('v'), Direction::Click))
+            .and_then(|_| enigo.key(modifier, Direction::Release))
+            .map_err(|e| AppError::System(format!("Failed to simulate paste: {}", e)))
+    })
+    .await?
+}
+
+/// 内部函数：按模式写入纯文本并粘贴
+async fn paste_text(app: &AppHandle, text: &str, mode: PasteMode) -> AppResult<()> {
+    let text = transform_text(text, mode);
+    clipboard::write_text(app, &text)?;
+    simulate_paste(app).await
+}
+
+/// 粘贴历史记录（文本历史或分组内容）
+///
+/// 未指定模式时使用配置中的默认粘贴模式
+#[tauri::command]
+pub async fn paste_item(
+    app: AppHandle,
+    id: String,
+    mode: Option<PasteMode>,
+) -> AppResult<()> {
+    let mode = match mode {
+        Some(mode) => mode,
+        None => AppConfig::current(&app)?.paste_mode,
+    };
+
+    let pool = db::pool(&app).await?;
+    let content = db::get_item_content(&pool, &id).await?;
+    paste_text(&app, &content, mode).await?;
+    db::record_usage(&pool, &id).await
+}
+
+/// 记录一次使用（前端复制历史记录或分组内容到剪切板后调用），用于按使用频率排序
+#[tauri::command]
+pub async fn record_item_usage(app: AppHandle, id: String) -> AppResult<()> {
+    let pool = db::pool(&app).await?;
+    db::record_usage(&pool, &id).await
+}
+
+/// 按默认粘贴模式粘贴第 n 条（从1开始，按配置的历史排序方式）文本历史
+pub async fn paste_recent(app: &AppHandle, n: u32) -> AppResult<()> {
+    if n == 0 {
+        return Err(AppError::InvalidInput("History index starts at 1".to_string()));
+    }
+
+    let config = AppConfig::current(app)?;
+    let pool = db::pool(app).await?;
+    let item = db::query_cut_items(&pool, None, Some(n), config.history_sort)
+        .await?
+        .into_iter()
+        .nth(n as usize - 1)
+        .ok_or_else(|| AppError::NotFound(format!("No history item at position {}", n)))?;
+
+    let content = db::get_item_content(&pool, &item.id).await?;
+    paste_text(app, &content, config.paste_mode).await?;
+    db::record_usage(&pool, &item.id).await
+}
+
+/// 以指定模式粘贴当前剪切板中的文本（全局快捷键调用）
+pub async fn paste_clipboard(app: &AppHandle, mode: PasteMode) -> AppResult<()> {
+    let text = app
+        .clipboard()
+        .read_text()
+        .map_err(|e| AppError::Clipboard(format!("Failed to read clipboard: {}", e)))?;
+
+    paste_text(app, &text, mode).await
+}
+
+/// 注册按默认模式粘贴剪切板内容的全局快捷键
+///
+/// previous 为之前注册的快捷键，会先被注销；shortcut 为空时只注销不注册
+#[cfg(desktop)]
+pub fn register_paste_shortcut(
+    app: &AppHandle,
+    previous: Option<&str>,
+    shortcut: &str,
+    mode: PasteMode,
+) -> AppResult<()> {
+    use tauri_plugin_global_shortcut::{GlobalShortcutExt, ShortcutState};
+
+    let global_shortcut = app.global_shortcut();
+    if let Some(previous) = previous.filter(|previous| !previous.trim().is_empty()) {
+        let _ = global_shortcut.unregister(previous);
+    }
+    if shortcut.trim().is_empty() {
+        return Ok(());
+    }
+
+    global_shortcut
+        .on_shortcut(shortcut, move |app, _shortcut, event| {
+            if event.state == ShortcutState::Released {
+                let app = app.clone();
+                tauri::async_runtime::spawn(async move {
+                    if let Err(e) = paste_clipboard(&app, mode).await {
+                        tracing::error!("Failed to paste clipboard: {}", e);
+                    }
+                });
+            }
+        })
+        .map_err(|e| AppError::System(format!("Failed to register shortcut {}: {}", shortcut, e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEXT: &str = "  第一行\r\n第二行\r第三行\n\t第四行  ";
+
+    #[test]
+    fn plain_keeps_text() {
+        assert_eq!(transform_text(TEXT, PasteMode::Plain), TEXT);
+    }
+
+    #[test]
+    fn collapse_whitespace_joins_words() {
+        assert_eq!(transform_text(TEXT, PasteMode::CollapseWhitespace), "第一行 第二行 第三行 第四行");
+    }
+
+    #[test]
+    fn line_endings_are_normalized() {
+        assert_eq!(transform_text(TEXT, PasteMode::Lf), "  第一行\n第二行\n第三行\n\t第四行  ");
+        assert_eq!(transform_text(TEXT, PasteMode::Crlf), "  第一行\r\n第二行\r\n第三行\r\n\t第四行  ");
+        // 已经是 CRLF 的换行不会重复转换
+        assert_eq!(transform_text("a\r\nb", PasteMode::Crlf), "a\r\nb");
+    }
+}
@@ -0,0 +1,276 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use sqlx::sqlite::SqlitePool;
+use tauri::AppHandle;
+
+use crate::db::{self, CutItem, GroupItem, ImageItem};
+use crate::error::{AppError, AppResult};
+
+/// 可以添加标签的记录类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ItemType {
+    /// 文本历史（CutItems 表）
+    CutItem,
+    /// 图片历史（ImageItems 表）
+    ImageItem,
+    /// 分组内容（GroupItems 表）
+    GroupItem,
+}
+
+impl ItemType {
+    /// 记录所在的表
+    pub fn table(self) -> &'static str {
+        match self {
+            ItemType::CutItem => "CutItems",
+            ItemType::ImageItem => "ImageItems",
+            ItemType::GroupItem => "GroupItems",
+        }
+    }
+
+    /// ItemTags.itemType、ItemRevisions.itemType 中保存的值
+    pub fn name(self) -> &'static str {
+        match self {
+            ItemType::CutItem => "cut_item",
+            ItemType::ImageItem => "image_item",
+            ItemType::GroupItem => "group_item",
+        }
+    }
+}
+
+/// 自动标签规则：新记录的文本内容匹配正则表达式时添加标签
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AutoTagRule {
+    /// 正则表达式
+    pub pattern: String,
+    /// 标签名
+    pub tag: String,
+}
+
+impl AutoTagRule {
+    /// 规则是否可用（正则可以编译且标签名不为空）
+    pub fn is_valid(&self) -> bool {
+        !self.tag.trim().is_empty() && Regex::new(&self.pattern).is_ok()
+    }
+}
+
+/// 标签及使用该标签的记录数
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+#[sqlx(rename_all = "camelCase")]
+pub struct TagCount {
+    pub id: String,
+    pub name: String,
+    pub count: i64,
+}
+
+/// 带有某个标签的全部记录
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TaggedItems {
+    pub cut_items: Vec<CutItem>,
+    pub image_items: Vec<ImageItem>,
+    pub group_items: Vec<GroupItem>,
+}
+
+/// 内部函数：规范化标签名（去掉首尾空白），为空时返回错误
+fn normalize_tag(tag: &str) -> AppResult<&str> {
+    let tag = tag.trim();
+    if tag.is_empty() {
+        return Err(AppError::InvalidInput("Tag name cannot be empty".to_string()));
+    }
+    Ok(tag)
+}
+
+/// 内部函数：获取标签 ID，标签不存在时创建（标签名不区分大小写）
+async fn ensure_tag(pool: &SqlitePool, name: &str) -> AppResult<String> {
+    sqlx::query("INSERT OR IGNORE INTO Tags (id, name, createTime) VALUES (?, ?, ?)")
+        .bind(uuid::Uuid::new_v4().to_string())
+        .bind(name)
+        .bind(db::now_string())
+        .execute(pool)
+        .await
+        .map_err(|e| AppError::Database(format!("Failed to create tag: {}", e)))?;
+
+    sqlx::query_scalar("SELECT id FROM Tags WHERE name = ?")
+        .bind(name)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| AppError::Database(format!("Failed to query tag: {}", e)))
+}
+
+/// 给记录添加标签，已有该标签时不做修改
+pub async fn tag_item(pool: &SqlitePool, item_id: &str, item_type: ItemType, tag: &str) -> AppResult<()> {
+    let tag = normalize_tag(tag)?;
+
+    let exists: Option<i64> = sqlx::query_scalar(&format!("SELECT 1 FROM {} WHERE id = ?", item_type.table()))
+        .bind(item_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| AppError::Database(format!("Failed to query item: {}", e)))?;
+    if exists.is_none() {
+        return Err(AppError::NotFound(format!("Item not found: {}", item_id)));
+    }
+
+    let tag_id = ensure_tag(pool, tag).await?;
+    sqlx::query("INSERT OR IGNORE INTO ItemTags (tagId, itemId, itemType, createTime) VALUES (?, ?, ?, ?)")
+        .bind(&tag_id)
+        .bind(item_id)
+        .bind(item_type.name())
+        .bind(db::now_string())
+        .execute(pool)
+        .await
+        .map_err(|e| AppError::Database(format!("Failed to tag item: {}", e)))?;
+
+    Ok(())
+}
+
+/// 按自动标签规则给新记录添加标签，无效的规则会被跳过
+pub async fn apply_auto_tags(
+    pool: &SqlitePool,
+    item_id: &str,
+    item_type: ItemType,
+    content: &str,
+    rules: &[AutoTagRule],
+) -> AppResult<()> {
+    for rule in rules {
+        let Ok(regex) = Regex::new(&rule.pattern) else {
+            continue;
+        };
+        if !rule.tag.trim().is_empty() && regex.is_match(content) {
+            tag_item(pool, item_id, item_type, &rule.tag).await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// 给记录添加标签
+#[tauri::command]
+pub async fn add_item_tag(app: AppHandle, item_id: String, item_type: ItemType, tag: String) -> AppResult<()> {
+    let pool = db::pool(&app).await?;
+    tag_item(&pool, &item_id, item_type, &tag).await
+}
+
+/// 移除记录的标签，不再被任何记录使用的标签一并删除
+#[tauri::command]
+pub async fn remove_item_tag(app: AppHandle, item_id: String, tag: String) -> AppResult<()> {
+    let tag = normalize_tag(&tag)?;
+    let pool = db::pool(&app).await?;
+
+    let result = sqlx::query("DELETE FROM ItemTags WHERE itemId = ? AND tagId = (SELECT id FROM Tags WHERE name = ?)")
+        .bind(&item_id)
+        .bind(tag)
+        .execute(&pool)
+        .await
+        .map_err(|e| AppError::Database(format!("Failed to remove tag: {}", e)))?;
+    if result.rows_affected() == 0 {
+        return Err(AppError::NotFound(format!("Item {} has no tag {}", item_id, tag)));
+    }
+
+    sqlx::query("DELETE FROM Tags WHERE name = ? AND NOT EXISTS (SELECT 1 FROM ItemTags WHERE tagId = Tags.id)")
+        .bind(tag)
+        .execute(&pool)
+        .await
+        .map_err(|e| AppError::Database(format!("Failed to delete tag: {}", e)))?;
+
+    Ok(())
+}
+
+/// 查询记录的全部标签名
+#[tauri::command]
+pub async fn get_item_tags(app: AppHandle, item_id: String) -> AppResult<Vec<String>> {
+    let pool = db::pool(&app).await?;
+    sqlx::query_scalar(
+        "SELECT Tags.name FROM ItemTags JOIN Tags ON Tags.id = ItemTags.tagId
+         WHERE ItemTags.itemId = ? ORDER BY Tags.name",
+    )
+    .bind(&item_id)
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| AppError::Database(format!("Failed to query item tags: {}", e)))
+}
+
+/// 查询全部标签及各标签的记录数（不含回收站中的记录），按记录数倒序
+pub async fn tag_counts(pool: &SqlitePool) -> AppResult<Vec<TagCount>> {
+    sqlx::query_as::<_, TagCount>(
+        "SELECT Tags.id, Tags.name, COUNT(Items.id) AS count
+         FROM Tags
+         LEFT JOIN ItemTags ON ItemTags.tagId = Tags.id
+         LEFT JOIN (
+             SELECT id FROM CutItems WHERE deletedAt IS NULL
+             UNION ALL SELECT id FROM ImageItems WHERE deletedAt IS NULL
+             UNION ALL SELECT id FROM GroupItems
+         ) AS Items ON Items.id = ItemTags.itemId
+         GROUP BY Tags.id ORDER BY count DESC, Tags.name",
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| AppError::Database(format!("Failed to query tags: {}", e)))
+}
+
+/// 查询全部标签及各标签的记录数，按记录数倒序
+#[tauri::command]
+pub async fn list_tags(app: AppHandle) -> AppResult<Vec<TagCount>> {
+    let pool = db::pool(&app).await?;
+    tag_counts(&pool).await
+}
+
+/// 查询带有指定标签的文本历史、图片历史（不含回收站）和分组内容
+#[tauri::command]
+pub async fn list_items_by_tag(app: AppHandle, tag: String) -> AppResult<TaggedItems> {
+    let tag = normalize_tag(&tag)?;
+    let pool = db::pool(&app).await?;
+    let filter = "id IN (SELECT ItemTags.itemId FROM ItemTags JOIN Tags ON Tags.id = ItemTags.tagId WHERE Tags.name = ?)";
+
+    let cut_items = sqlx::query_as::<_, CutItem>(&format!(
+        "SELECT {} FROM CutItems WHERE deletedAt IS NULL AND {} ORDER BY createTime DESC",
+        db::CUT_ITEM_COLUMNS,
+        filter
+    ))
+    .bind(tag)
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| AppError::Database(format!("Failed to query items: {}", e)))?;
+
+    let image_items = sqlx::query_as::<_, ImageItem>(&format!(
+        "SELECT * FROM ImageItems WHERE deletedAt IS NULL AND {} ORDER BY createTime DESC",
+        filter
+    ))
+    .bind(tag)
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| AppError::Database(format!("Failed to query images: {}", e)))?;
+
+    let group_items = sqlx::query_as::<_, GroupItem>(&format!(
+        "SELECT id, groupId, content, title, createTime FROM GroupItems WHERE {} ORDER BY createTime DESC",
+        filter
+    ))
+    .bind(tag)
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| AppError::Database(format!("Failed to query group items: {}", e)))?;
+
+    Ok(TaggedItems { cut_items, image_items, group_items })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::migrations;
+
+    #[tokio::test]
+    async fn trashed_items_are_not_counted() {
+        let pool = migrations::migrated_pool().await;
+        let kept = db::insert_cut_item(&pool, "保留", db::SELECTION_CLIPBOARD, usize::MAX).await.unwrap();
+        let trashed = db::insert_cut_item(&pool, "删除", db::SELECTION_CLIPBOARD, usize::MAX).await.unwrap();
+        tag_item(&pool, &kept.id, ItemType::CutItem, "工作").await.unwrap();
+        tag_item(&pool, &trashed.id, ItemType::CutItem, "工作").await.unwrap();
+
+        db::trash_item(&pool, &trashed.id).await.unwrap();
+        let counts = tag_counts(&pool).await.unwrap();
+        assert_eq!(counts.len(), 1);
+        assert_eq!(counts[0].name, "工作");
+        assert_eq!(counts[0].count, 1);
+    }
+}
@@ -1,10 +1,46 @@
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
 use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::ffi::OsStr;
 use std::fs;
-use std::path::PathBuf;
-use tauri::{AppHandle, Manager};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, RwLock};
+use tauri::{AppHandle, Emitter, Manager, State};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+use crate::commands::image_processor::{parse_hex_color, ImageOutputFormat, PngCompression};
+use crate::commands::paste::PasteMode;
+use crate::commands::tags::AutoTagRule;
+use crate::db::{self, HistorySort};
+use crate::error::{AppError, AppResult, FieldError};
+use crate::logging::{self, LogLevel};
+
+/// 配置更新事件，载荷为新的配置，所有窗口都会收到
+pub const CONFIG_UPDATED_EVENT: &str = "config-updated";
+
+/// 配置文件名
+const CONFIG_FILE_NAME: &str = "config.json";
+
+/// 配置文件监听器，放入 Tauri 托管状态中保持存活
+struct ConfigWatcher(#[allow(dead_code)] Mutex<RecommendedWatcher>);
+
+/// 当前配置版本，修改配置结构时递增并在 MIGRATIONS 中添加升级函数
+pub const CONFIG_VERSION: u32 = 1;
+
+/// 配置升级函数，MIGRATIONS[n] 把版本 n 的配置升级到版本 n + 1
+const MIGRATIONS: &[fn(&mut Map<String, Value>)] = &[migrate_v0_to_v1];
+
+/// 版本0（没有 version 字段的旧配置）升级到版本1
+///
+/// 版本1 只引入了版本号，字段保持兼容，缺失的字段由 serde 默认值补齐
+fn migrate_v0_to_v1(_config: &mut Map<String, Value>) {}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct AppConfig {
+    /// 配置版本，缺失时视为版本0
+    #[serde(default)]
+    pub version: u32,
+    
     /// 文本历史记录最大条数
     #[serde(default = "default_max_text_history")]
     pub max_text_history: u32,
@@ -13,9 +49,121 @@ pub struct AppConfig {
     #[serde(default = "default_max_image_history")]
     pub max_image_history: u32,
     
+    /// 历史记录排序方式（按时间或按使用频率和最近使用时间）
+    #[serde(default)]
+    pub history_sort: HistorySort,
+    
+    /// 超过该大小（KB）的文本压缩存储，列表中只显示预览
+    #[serde(default = "default_compress_threshold_kb")]
+    pub compress_threshold_kb: u32,
+    
+    /// 文本最大大小（MB），超过时不记录
+    #[serde(default = "default_max_text_size_mb")]
+    pub max_text_size_mb: u32,
+    
+    /// 回收站中的记录保留天数，超过后永久删除
+    #[serde(default = "default_trash_retention_days")]
+    pub trash_retention_days: u32,
+    
+    /// 每条记录保留的历史版本数
+    #[serde(default = "default_max_revisions_per_item")]
+    pub max_revisions_per_item: u32,
+    
+    /// 定期整理数据库（VACUUM）释放空间
+    #[serde(default)]
+    pub auto_vacuum_enabled: bool,
+    
+    /// 自动整理数据库的间隔天数
+    #[serde(default = "default_auto_vacuum_interval_days")]
+    pub auto_vacuum_interval_days: u32,
+    
+    /// 自动标签规则，新记录的文本（图片为识别出的文字）匹配时添加对应标签
+    #[serde(default)]
+    pub auto_tag_rules: Vec<AutoTagRule>,
+    
     /// 开机自启动
     #[serde(default = "default_auto_start")]
     pub auto_start: bool,
+    
+    /// 启动时显示主窗口（开机自启动时总是在托盘中后台运行）
+    #[serde(default = "default_show_window_on_launch")]
+    pub show_window_on_launch: bool,
+    
+    /// 默认粘贴模式
+    #[serde(default)]
+    pub paste_mode: PasteMode,
+    
+    /// 按默认模式粘贴剪切板内容的全局快捷键，为空时不注册（默认不注册，避免占用其他应用的快捷键）
+    #[serde(default = "default_paste_shortcut")]
+    pub paste_shortcut: String,
+    
+    /// 记录 X11 鼠标选区（PRIMARY）内容，仅 Linux
+    #[serde(default)]
+    pub primary_capture_enabled: bool,
+    
+    /// 鼠标选区与剪切板双向同步，仅 Linux
+    #[serde(default)]
+    pub primary_sync_enabled: bool,
+    
+    /// 鼠标选区稳定多久（毫秒）后才记录或同步，避免拖动选择时产生大量记录
+    #[serde(default = "default_primary_debounce_ms")]
+    pub primary_debounce_ms: u32,
+    
+    /// 多设备同步
+    #[serde(default)]
+    pub sync_enabled: bool,
+    
+    /// 同步目录（例如 Syncthing、Nextcloud 同步的文件夹）
+    #[serde(default)]
+    pub sync_folder: Option<String>,
+    
+    /// 自动同步间隔（秒）
+    #[serde(default = "default_sync_interval_secs")]
+    pub sync_interval_secs: u32,
+    
+    /// 图片文字识别（OCR）
+    #[serde(default)]
+    pub ocr_enabled: bool,
+    
+    /// OCR识别语言（Tesseract 语言代码）
+    #[serde(default = "default_ocr_language")]
+    pub ocr_language: String,
+    
+    /// Tesseract 语言数据目录，为空时使用系统默认目录
+    #[serde(default)]
+    pub ocr_data_path: Option<String>,
+    
+    /// 图片保存格式
+    #[serde(default)]
+    pub image_format: ImageOutputFormat,
+    
+    /// 有损压缩质量（1-100）
+    #[serde(default = "default_image_quality")]
+    pub image_quality: u8,
+    
+    /// 图片最大宽度，超过时等比缩小，0 表示不限制
+    #[serde(default)]
+    pub image_max_width: u32,
+    
+    /// 图片最大高度，超过时等比缩小，0 表示不限制
+    #[serde(default)]
+    pub image_max_height: u32,
+    
+    /// PNG 压缩级别
+    #[serde(default)]
+    pub png_compression: PngCompression,
+    
+    /// 把透明图片合成到背景色上（用于不支持透明通道的目标应用）
+    #[serde(default)]
+    pub image_flatten_alpha: bool,
+    
+    /// 合成透明图片使用的背景色（#RRGGBB，也接受 #RRGGBBAA，透明度不起作用）
+    #[serde(default = "default_image_background_color")]
+    pub image_background_color: String,
+    
+    /// 日志级别（error / warn / info / debug / trace）
+    #[serde(default)]
+    pub log_level: LogLevel,
 }
 
 fn default_max_text_history() -> u32 {
@@ -26,103 +174,636 @@ fn default_max_image_history() -> u32 {
     30
 }
 
+fn default_compress_threshold_kb() -> u32 {
+    64
+}
+
+fn default_max_text_size_mb() -> u32 {
+    20
+}
+
+fn default_trash_retention_days() -> u32 {
+    30
+}
+
+fn default_max_revisions_per_item() -> u32 {
+    20
+}
+
+fn default_auto_vacuum_interval_days() -> u32 {
+    7
+}
+
 fn default_auto_start() -> bool {
     true
 }
 
+fn default_show_window_on_launch() -> bool {
+    true
+}
+
+fn default_paste_shortcut() -> String {
+    String::new()
+}
+
+fn default_primary_debounce_ms() -> u32 {
+    500
+}
+
+fn default_sync_interval_secs() -> u32 {
+    60
+}
+
+fn default_ocr_language() -> String {
+    "chi_sim+eng".to_string()
+}
+
+fn default_image_quality() -> u8 {
+    80
+}
+
+fn default_image_background_color() -> String {
+    "#FFFFFF".to_string()
+}
+
 impl Default for AppConfig {
     fn default() -> Self {
         Self {
+            version: CONFIG_VERSION,
             max_text_history: default_max_text_history(),
             max_image_history: default_max_image_history(),
+            history_sort: HistorySort::default(),
+            compress_threshold_kb: default_compress_threshold_kb(),
+            max_text_size_mb: default_max_text_size_mb(),
+            trash_retention_days: default_trash_retention_days(),
+            max_revisions_per_item: default_max_revisions_per_item(),
+            auto_vacuum_enabled: false,
+            auto_vacuum_interval_days: default_auto_vacuum_interval_days(),
+            auto_tag_rules: Vec::new(),
             auto_start: default_auto_start(),
+            show_window_on_launch: default_show_window_on_launch(),
+            paste_mode: PasteMode::default(),
+            paste_shortcut: default_paste_shortcut(),
+            primary_capture_enabled: false,
+            primary_sync_enabled: false,
+            primary_debounce_ms: default_primary_debounce_ms(),
+            sync_enabled: false,
+            sync_folder: None,
+            sync_interval_secs: default_sync_interval_secs(),
+            ocr_enabled: false,
+            ocr_language: default_ocr_language(),
+            ocr_data_path: None,
+            image_format: ImageOutputFormat::default(),
+            image_quality: default_image_quality(),
+            image_max_width: 0,
+            image_max_height: 0,
+            png_compression: PngCompression::default(),
+            image_flatten_alpha: false,
+            image_background_color: default_image_background_color(),
+            log_level: LogLevel::default(),
         }
     }
 }
 
 impl AppConfig {
     /// 获取配置文件路径
-    fn get_config_path(app: &AppHandle) -> Result<PathBuf, String> {
+    fn get_config_path(app: &AppHandle) -> AppResult<PathBuf> {
         let app_data_dir = app.path()
             .app_data_dir()
-            .map_err(|e| format!("Failed to get app data dir: {}", e))?;
+            .map_err(|e| AppError::Io(format!("Failed to get app data dir: {}", e)))?;
         
         // 确保目录存在
         fs::create_dir_all(&app_data_dir)
-            .map_err(|e| format!("Failed to create app data dir: {}", e))?;
+            .map_err(|e| AppError::Io(format!("Failed to create app data dir: {}", e)))?;
         
-        Ok(app_data_dir.join("config.json"))
+        Ok(app_data_dir.join(CONFIG_FILE_NAME))
     }
     
     /// 从文件加载配置
-    pub fn load(app: &AppHandle) -> Result<Self, String> {
-        let config_path = Self::get_config_path(app)?;
-        
-        if config_path.exists() {
-            let content = fs::read_to_string(&config_path)
-                .map_err(|e| format!("Failed to read config file: {}", e))?;
-            
-            let config: AppConfig = serde_json::from_str(&content)
-                .map_err(|e| format!("Failed to parse config: {}", e))?;
-            
-            Ok(config)
-        } else {
+    ///
+    /// 旧版本配置会先升级到当前版本，不合法的字段恢复为默认值；
+    /// 文件损坏时备份为 config.json.<时间>.bak 并使用默认配置
+    pub fn load(app: &AppHandle) -> AppResult<Self> {
+        Self::load_from(&Self::get_config_path(app)?)
+    }
+    
+    /// 内部函数：从指定路径加载配置
+    fn load_from(config_path: &Path) -> AppResult<Self> {
+        if !config_path.exists() {
             // 配置文件不存在，返回默认配置
-            Ok(AppConfig::default())
+            return Ok(AppConfig::default());
+        }
+        
+        let content = fs::read_to_string(config_path)
+            .map_err(|e| AppError::Io(format!("Failed to read config file: {}", e)))?;
+        
+        match Self::from_content(&content) {
+            Ok(config) => Ok(config),
+            Err(e) => {
+                tracing::warn!("Config file is corrupt, falling back to defaults: {}", e);
+                Self::backup_corrupt_file(config_path)?;
+                Ok(AppConfig::default())
+            }
         }
     }
     
     /// 保存配置到文件
-    pub fn save(&self, app: &AppHandle) -> Result<(), String> {
+    ///
+    /// 先写入临时文件再重命名，避免写入中途崩溃损坏配置文件
+    pub fn save(&self, app: &AppHandle) -> AppResult<()> {
+        self.validate().map_err(AppError::Validation)?;
+        
         let config_path = Self::get_config_path(app)?;
+        let tmp_path = config_path.with_extension("json.tmp");
+        
+        // 保存时总是写入当前版本号
+        let config = AppConfig { version: CONFIG_VERSION, ..self.clone() };
+        let content = serde_json::to_string_pretty(&config)
+            .map_err(|e| AppError::Config(format!("Failed to serialize config: {}", e)))?;
         
-        let content = serde_json::to_string_pretty(self)
-            .map_err(|e| format!("Failed to serialize config: {}", e))?;
+        let mut file = fs::File::create(&tmp_path)
+            .map_err(|e| AppError::Io(format!("Failed to create temp config file: {}", e)))?;
+        file.write_all(content.as_bytes())
+            .and_then(|_| file.sync_all())
+            .map_err(|e| AppError::Io(format!("Failed to write temp config file: {}", e)))?;
+        drop(file);
         
-        fs::write(&config_path, content)
-            .map_err(|e| format!("Failed to write config file: {}", e))?;
+        fs::rename(&tmp_path, &config_path)
+            .map_err(|e| AppError::Io(format!("Failed to replace config file: {}", e)))?;
         
         Ok(())
     }
+    
+    /// 校验配置，返回所有不合法字段
+    pub fn validate(&self) -> Result<(), Vec<FieldError>> {
+        let mut errors = Vec::new();
+        
+        if !(1..=100_000).contains(&self.max_text_history) {
+            errors.push(FieldError {
+                field: "max_text_history",
+                message: "必须在 1 到 100000 之间".to_string(),
+            });
+        }
+        if !(1..=10_000).contains(&self.max_image_history) {
+            errors.push(FieldError {
+                field: "max_image_history",
+                message: "必须在 1 到 10000 之间".to_string(),
+            });
+        }
+        if !(1..=102_400).contains(&self.compress_threshold_kb) {
+            errors.push(FieldError {
+                field: "compress_threshold_kb",
+                message: "必须在 1 到 102400 之间".to_string(),
+            });
+        }
+        if !(1..=1024).contains(&self.max_text_size_mb) {
+            errors.push(FieldError {
+                field: "max_text_size_mb",
+                message: "必须在 1 到 1024 之间".to_string(),
+            });
+        }
+        if !(1..=3650).contains(&self.trash_retention_days) {
+            errors.push(FieldError {
+                field: "trash_retention_days",
+                message: "必须在 1 到 3650 之间".to_string(),
+            });
+        }
+        if !(1..=1000).contains(&self.max_revisions_per_item) {
+            errors.push(FieldError {
+                field: "max_revisions_per_item",
+                message: "必须在 1 到 1000 之间".to_string(),
+            });
+        }
+        if !(1..=365).contains(&self.auto_vacuum_interval_days) {
+            errors.push(FieldError {
+                field: "auto_vacuum_interval_days",
+                message: "必须在 1 到 365 之间".to_string(),
+            });
+        }
+        if !self.auto_tag_rules.iter().all(AutoTagRule::is_valid) {
+            errors.push(FieldError {
+                field: "auto_tag_rules",
+                message: "包含无效的正则表达式或空标签名".to_string(),
+            });
+        }
+        if self.primary_debounce_ms > 10_000 {
+            errors.push(FieldError {
+                field: "primary_debounce_ms",
+                message: "必须在 0 到 10000 之间".to_string(),
+            });
+        }
+        if self.sync_enabled && self.sync_folder.as_deref().map_or(true, |folder| folder.trim().is_empty()) {
+            errors.push(FieldError {
+                field: "sync_folder",
+                message: "开启同步时必须设置同步目录".to_string(),
+            });
+        }
+        if !(10..=86_400).contains(&self.sync_interval_secs) {
+            errors.push(FieldError {
+                field: "sync_interval_secs",
+                message: "必须在 10 到 86400 之间".to_string(),
+            });
+        }
+        if self.ocr_language.trim().is_empty() {
+            errors.push(FieldError {
+                field: "ocr_language",
+                message: "不能为空".to_string(),
+            });
+        }
+        if !(1..=100).contains(&self.image_quality) {
+            errors.push(FieldError {
+                field: "image_quality",
+                message: "必须在 1 到 100 之间".to_string(),
+            });
+        }
+        if parse_hex_color(&self.image_background_color).is_err() {
+            errors.push(FieldError {
+                field: "image_background_color",
+                message: "必须是 #RRGGBB 或 #RRGGBBAA 格式的颜色（# 可省略）".to_string(),
+            });
+        }
+        
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+    
+    /// 内部函数：把不合法的字段恢复为默认值
+    fn reset_fields(&mut self, errors: &[FieldError]) {
+        let defaults = AppConfig::default();
+        for error in errors {
+            match error.field {
+                "max_text_history" => self.max_text_history = defaults.max_text_history,
+                "max_image_history" => self.max_image_history = defaults.max_image_history,
+                "compress_threshold_kb" => self.compress_threshold_kb = defaults.compress_threshold_kb,
+                "max_text_size_mb" => self.max_text_size_mb = defaults.max_text_size_mb,
+                "trash_retention_days" => self.trash_retention_days = defaults.trash_retention_days,
+                "max_revisions_per_item" => self.max_revisions_per_item = defaults.max_revisions_per_item,
+                "auto_vacuum_interval_days" => self.auto_vacuum_interval_days = defaults.auto_vacuum_interval_days,
+                // 只移除无效的规则
+                "auto_tag_rules" => self.auto_tag_rules.retain(AutoTagRule::is_valid),
+                "primary_debounce_ms" => self.primary_debounce_ms = defaults.primary_debounce_ms,
+                // 没有同步目录时关闭同步
+                "sync_folder" => self.sync_enabled = false,
+                "sync_interval_secs" => self.sync_interval_secs = defaults.sync_interval_secs,
+                "ocr_language" => self.ocr_language = defaults.ocr_language.clone(),
+                "image_quality" => self.image_quality = defaults.image_quality,
+                "image_background_color" => {
+                    self.image_background_color = defaults.image_background_color.clone()
+                }
+                _ => {}
+            }
+        }
+    }
+    
+    /// 内部函数：解析配置内容，不合法的字段恢复为默认值
+    fn from_content(content: &str) -> AppResult<Self> {
+        let mut config = Self::parse(content)?;
+        if let Err(errors) = config.validate() {
+            tracing::warn!("Invalid config fields reset to defaults: {}", AppError::Validation(errors.clone()));
+            config.reset_fields(&errors);
+        }
+        Ok(config)
+    }
+    
+    /// 内部函数：解析配置内容并升级到当前版本
+    fn parse(content: &str) -> AppResult<Self> {
+        let value: Value = serde_json::from_str(content)
+            .map_err(|e| AppError::Config(format!("Failed to parse config: {}", e)))?;
+        
+        let Value::Object(mut map) = value else {
+            return Err(AppError::Config("Config root must be an object".to_string()));
+        };
+        
+        let version = map.get("version").and_then(Value::as_u64).unwrap_or(0) as usize;
+        // 更高版本写入的配置无法降级，按当前结构尽量读取（未知字段会被忽略）
+        for migrate in MIGRATIONS.iter().skip(version) {
+            migrate(&mut map);
+        }
+        map.insert("version".to_string(), Value::from(CONFIG_VERSION.max(version as u32)));
+        
+        serde_json::from_value(Value::Object(map))
+            .map_err(|e| AppError::Config(format!("Failed to parse config: {}", e)))
+    }
+    
+    /// 内部函数：备份损坏的配置文件到 config.json.<时间>.bak，不覆盖之前的备份
+    fn backup_corrupt_file(config_path: &Path) -> AppResult<()> {
+        let timestamp = chrono::Local::now().format("%Y%m%d-%H%M%S%.6f");
+        let backup_path = config_path.with_extension(format!("json.{}.bak", timestamp));
+        fs::rename(config_path, &backup_path)
+            .map_err(|e| AppError::Io(format!("Failed to back up corrupt config file: {}", e)))
+    }
 }
 
-/// Tauri命令：获取配置
-#[tauri::command]
-pub fn get_config(app: AppHandle) -> Result<AppConfig, String> {
-    AppConfig::load(&app)
+impl AppConfig {
+    /// 读取当前生效的配置
+    pub fn current(app: &AppHandle) -> AppResult<Self> {
+        config_state(app)?
+            .read()
+            .map(|config| config.clone())
+            .map_err(|e| AppError::Internal(format!("Failed to lock config: {}", e)))
+    }
+
+    /// 压缩存储的阈值（字节）
+    pub fn compress_threshold_bytes(&self) -> usize {
+        self.compress_threshold_kb as usize * 1024
+    }
+
+    /// 文本最大大小（字节）
+    pub fn max_text_bytes(&self) -> usize {
+        self.max_text_size_mb as usize * 1024 * 1024
+    }
 }
 
-/// Tauri命令：保存配置
-#[tauri::command]
-pub fn save_config(app: AppHandle, config: AppConfig) -> Result<(), String> {
-    config.save(&app)
+/// 内部函数：获取托管的配置状态
+fn config_state(app: &AppHandle) -> AppResult<State<'_, RwLock<AppConfig>>> {
+    app.try_state::<RwLock<AppConfig>>()
+        .ok_or_else(|| AppError::Internal("Config state is not initialized".to_string()))
 }
 
-/// Tauri命令：设置自启动
-#[tauri::command]
-pub async fn set_auto_start(app: AppHandle, enable: bool) -> Result<(), String> {
+/// 初始化配置（启动时调用一次）：从文件加载到托管状态并应用
+pub fn init_config(app: &AppHandle) -> AppResult<()> {
+    let config = AppConfig::load(app).unwrap_or_default();
+    app.manage(RwLock::new(config.clone()));
+    apply_changes(app, None, &config)
+}
+
+/// 内部函数：在写锁内基于当前配置生成新配置，与当前配置不同时替换并应用变化
+///
+/// persist 为 true 时先写入配置文件，写入失败时保持当前配置不变；返回更新后的配置
+fn modify_config(
+    app: &AppHandle,
+    persist: bool,
+    modify: impl FnOnce(&AppConfig) -> AppResult<AppConfig>,
+) -> AppResult<AppConfig> {
+    let (previous, config) = {
+        let mut current = config_state(app)?
+            .write()
+            .map_err(|e| AppError::Internal(format!("Failed to lock config: {}", e)))?;
+        let config = AppConfig { version: CONFIG_VERSION, ..modify(&current)? };
+        if *current == config {
+            return Ok(config);
+        }
+        if persist {
+            config.save(app)?;
+        }
+        (std::mem::replace(&mut *current, config.clone()), config)
+    };
+    
+    apply_changes(app, Some(&previous), &config)?;
+    Ok(config)
+}
+
+/// 内部函数：把部分字段合并到配置中
+fn merge_patch(current: &AppConfig, patch: Map<String, Value>) -> AppResult<AppConfig> {
+    let Value::Object(mut merged) = serde_json::to_value(current)
+        .map_err(|e| AppError::Config(format!("Failed to serialize config: {}", e)))?
+    else {
+        return Err(AppError::Internal("Config must serialize to an object".to_string()));
+    };
+    
+    for (field, value) in patch {
+        // 版本号由后端维护，忽略前端传入的值
+        if field == "version" {
+            continue;
+        }
+        if !merged.contains_key(&field) {
+            return Err(AppError::InvalidInput(format!("Unknown config field: {}", field)));
+        }
+        merged.insert(field, value);
+    }
+    
+    serde_json::from_value(Value::Object(merged))
+        .map_err(|e| AppError::InvalidInput(format!("Invalid config value: {}", e)))
+}
+
+/// 内部函数：应用配置变化，同步日志级别、自启动、全局快捷键和历史保留数量，并通知所有窗口
+///
+/// previous 为 None 时应用全部设置；图片编码、OCR 等设置在使用时读取，无需额外处理
+fn apply_changes(app: &AppHandle, previous: Option<&AppConfig>, config: &AppConfig) -> AppResult<()> {
+    let mut result = Ok(());
+    
+    if previous.map(|p| p.log_level) != Some(config.log_level) {
+        result = result.and(logging::set_level(app, config.log_level));
+    }
+    
+    if previous.map(|p| p.auto_start) != Some(config.auto_start) {
+        result = result.and(sync_auto_start(app, config.auto_start));
+    }
+    
+    #[cfg(desktop)]
+    if previous.map(|p| (p.paste_shortcut.as_str(), p.paste_mode))
+        != Some((config.paste_shortcut.as_str(), config.paste_mode))
+    {
+        result = result.and(crate::commands::paste::register_paste_shortcut(
+            app,
+            previous.map(|p| p.paste_shortcut.as_str()),
+            &config.paste_shortcut,
+            config.paste_mode,
+        ));
+    }
+    
+    if previous.map(|p| (p.max_text_history, p.max_image_history))
+        != Some((config.max_text_history, config.max_image_history))
+    {
+        let app = app.clone();
+        let (max_text, max_image) = (config.max_text_history, config.max_image_history);
+        tauri::async_runtime::spawn(async move {
+            let trimmed = match db::pool(&app).await {
+                Ok(pool) => db::enforce_retention(&pool, max_text, max_image).await,
+                Err(e) => Err(e),
+            };
+            if let Err(e) = trimmed {
+                tracing::error!("Failed to apply history limits: {}", e);
+            }
+        });
+    }
+    
+    result.and(app.emit(CONFIG_UPDATED_EVENT, config).map_err(AppError::from))
+}
+
+/// 监听配置文件，外部修改 config.json 后重新加载并应用
+///
+/// 监听所在目录而不是文件本身，原子写入的重命名也能被捕获；
+/// 文件内容不合法时保留当前配置
+pub fn watch_config(app: &AppHandle) -> AppResult<()> {
+    let config_path = AppConfig::get_config_path(app)?;
+    let config_dir = config_path
+        .parent()
+        .ok_or_else(|| AppError::Io("Config file has no parent dir".to_string()))?
+        .to_path_buf();
+    
+    let handle = app.clone();
+    let mut watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+        let Ok(event) = event else { return };
+        let touches_config = event
+            .paths
+            .iter()
+            .any(|path| path.file_name() == Some(OsStr::new(CONFIG_FILE_NAME)));
+        if !touches_config || !(event.kind.is_create() || event.kind.is_modify()) {
+            return;
+        }
+        
+        let reloaded = fs::read_to_string(&config_path)
+            .map_err(|e| AppError::Io(format!("Failed to read config file: {}", e)))
+            .and_then(|content| AppConfig::from_content(&content))
+            .and_then(|config| modify_config(&handle, false, |_| Ok(config)).map(|_| ()));
+        match reloaded {
+            Ok(()) => tracing::info!("Reloaded config file"),
+            Err(e) => tracing::error!("Failed to reload config: {}", e),
+        }
+    })
+    .map_err(|e| AppError::System(format!("Failed to create config watcher: {}", e)))?;
+    
+    watcher
+        .watch(&config_dir, RecursiveMode::NonRecursive)
+        .map_err(|e| AppError::System(format!("Failed to watch config dir: {}", e)))?;
+    
+    app.manage(ConfigWatcher(Mutex::new(watcher)));
+    Ok(())
+}
+
+/// 内部函数：按配置开启或关闭自启动
+fn sync_auto_start(app: &AppHandle, enable: bool) -> AppResult<()> {
     use tauri_plugin_autostart::ManagerExt;
     
     let auto_launch = app.autolaunch();
     
     if enable {
         auto_launch.enable()
-            .map_err(|e| format!("启用自启动失败: {}", e))?;
+            .map_err(|e| AppError::System(format!("Failed to enable autostart: {}", e)))
     } else {
         auto_launch.disable()
-            .map_err(|e| format!("禁用自启动失败: {}", e))?;
+            .map_err(|e| AppError::System(format!("Failed to disable autostart: {}", e)))
     }
-    
-    Ok(())
+}
+
+/// Tauri命令：获取配置
+#[tauri::command]
+pub fn get_config(app: AppHandle) -> AppResult<AppConfig> {
+    AppConfig::current(&app)
+}
+
+/// Tauri命令：更新配置
+///
+/// patch 只需包含要修改的字段，合并到当前配置后校验、保存并立即生效，返回更新后的配置
+#[tauri::command]
+pub fn update_config(app: AppHandle, patch: Map<String, Value>) -> AppResult<AppConfig> {
+    modify_config(&app, true, |current| merge_patch(current, patch))
+}
+
+/// Tauri命令：设置自启动
+#[tauri::command]
+pub async fn set_auto_start(app: AppHandle, enable: bool) -> AppResult<()> {
+    sync_auto_start(&app, enable)
 }
 
 /// Tauri命令：检查自启动状态
 #[tauri::command]
-pub async fn is_auto_start_enabled(app: AppHandle) -> Result<bool, String> {
+pub async fn is_auto_start_enabled(app: AppHandle) -> AppResult<bool> {
     use tauri_plugin_autostart::ManagerExt;
     
     let auto_launch = app.autolaunch();
     auto_launch.is_enabled()
-        .map_err(|e| format!("检查自启动状态失败: {}", e))
+        .map_err(|e| AppError::System(format!("Failed to check autostart status: {}", e)))
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn invalid_fields(config: &AppConfig) -> Vec<&'static str> {
+        config.validate().err().unwrap_or_default().iter().map(|error| error.field).collect()
+    }
+
+    #[test]
+    fn default_config_is_valid() {
+        assert!(AppConfig::default().validate().is_ok());
+    }
+
+    #[test]
+    fn validate_reports_every_invalid_field() {
+        let config = AppConfig {
+            max_text_history: 0,
+            sync_enabled: true,
+            sync_folder: Some(" ".to_string()),
+            image_background_color: "白色".to_string(),
+            ..AppConfig::default()
+        };
+        assert_eq!(invalid_fields(&config), vec!["max_text_history", "sync_folder", "image_background_color"]);
+
+        // 颜色可以省略 #，也可以带透明度
+        for color in ["#FFFFFF", "ffffff", "#FFFFFF80"] {
+            let config = AppConfig { image_background_color: color.to_string(), ..AppConfig::default() };
+            assert!(config.validate().is_ok(), "{}", color);
+        }
+    }
+
+    #[test]
+    fn invalid_fields_are_reset() {
+        let config = AppConfig::from_content(
+            r#"{
+                "version": 1,
+                "max_text_history": 0,
+                "max_image_history": 20,
+                "sync_enabled": true,
+                "image_background_color": "白色",
+                "auto_tag_rules": [
+                    { "pattern": "^https?://", "tag": "链接" },
+                    { "pattern": "(", "tag": "无效" }
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        let defaults = AppConfig::default();
+        assert_eq!(config.max_text_history, defaults.max_text_history);
+        assert_eq!(config.max_image_history, 20);
+        assert!(!config.sync_enabled);
+        assert_eq!(config.image_background_color, defaults.image_background_color);
+        assert_eq!(config.auto_tag_rules.len(), 1);
+        assert_eq!(config.auto_tag_rules[0].tag, "链接");
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn old_configs_are_migrated() {
+        assert_eq!(MIGRATIONS.len(), CONFIG_VERSION as usize);
+
+        // 没有版本号的旧配置升级到当前版本，已有字段保留
+        let config = AppConfig::parse(r#"{ "max_text_history": 300 }"#).unwrap();
+        assert_eq!(config.version, CONFIG_VERSION);
+        assert_eq!(config.max_text_history, 300);
+
+        // 更高版本的配置保留版本号
+        let config = AppConfig::parse(&format!(r#"{{ "version": {} }}"#, CONFIG_VERSION + 1)).unwrap();
+        assert_eq!(config.version, CONFIG_VERSION + 1);
+    }
+
+    #[test]
+    fn corrupt_file_is_backed_up() {
+        let dir = std::env::temp_dir().join(format!("cut-config-test-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        let config_path = dir.join(CONFIG_FILE_NAME);
+
+        // 多次损坏时每次都保留一份备份
+        for content in ["{ 损坏的配置", "[]"] {
+            fs::write(&config_path, content).unwrap();
+            assert_eq!(AppConfig::load_from(&config_path).unwrap(), AppConfig::default());
+            assert!(!config_path.exists());
+            std::thread::sleep(std::time::Duration::from_millis(5));
+        }
+
+        let mut backups: Vec<String> = fs::read_dir(&dir)
+            .unwrap()
+            .map(|entry| fs::read_to_string(entry.unwrap().path()).unwrap())
+            .collect();
+        backups.sort();
+        assert_eq!(backups, vec!["[]".to_string(), "{ 损坏的配置".to_string()]);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,780 @@
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sqlx::sqlite::{SqliteConnection, SqlitePool};
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+use tauri_plugin_sql::{DbInstances, DbPool};
+
+use crate::commands::image_processor::EncodedImage;
+use crate::commands::db_maintenance;
+use crate::commands::tags::{self, ItemType};
+use crate::config::AppConfig;
+use crate::error::{AppError, AppResult};
+use crate::sync;
+
+/// 数据库连接地址（与前端 Database.load 使用的地址保持一致）
+pub const DB_URL: &str = "sqlite:cut.db";
+
+/// 来自系统剪切板（CLIPBOARD）的记录
+pub const SELECTION_CLIPBOARD: &str = "clipboard";
+
+/// 来自 X11 鼠标选区（PRIMARY）的记录
+pub const SELECTION_PRIMARY: &str = "primary";
+
+/// 后端添加文本记录事件，载荷为新记录
+pub const CUT_ITEM_ADDED_EVENT: &str = "cut-item-added";
+
+/// 记录移到回收站事件，载荷为 TrashedItem，前端据此从列表移除并提示撤销
+pub const ITEM_TRASHED_EVENT: &str = "item-trashed";
+
+/// 记录从回收站恢复事件，载荷为 TrashedItem
+pub const ITEM_RESTORED_EVENT: &str = "item-restored";
+
+/// 数据库定期维护的间隔
+const MAINTENANCE_INTERVAL: std::time::Duration = std::time::Duration::from_secs(60 * 60);
+
+/// 查询 CutItem 时选择的列
+///
+/// 压缩存储的记录 content 列只有预览，完整内容需要通过 get_item_content 读取
+pub const CUT_ITEM_COLUMNS: &str = "id, content, createTime, selection, copyCount, pasteCount, lastUsedAt, deletedAt, \
+     preview, length, contentBlob IS NOT NULL AS compressed";
+
+/// 预览的最大字符数
+pub const PREVIEW_CHARS: usize = 200;
+
+/// zstd 压缩级别
+const ZSTD_LEVEL: i32 = 3;
+
+/// 综合使用次数和最近使用时间的排序分数：使用次数越多、距上次使用（没有使用过时为创建时间）越近分数越高，
+/// 分数随经过的天数衰减
+const FRECENCY_SCORE: &str = "(pasteCount + 1.0) / (julianday('now') - julianday(COALESCE(lastUsedAt, createTime)) + 1.0)";
+
+/// 历史记录排序方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HistorySort {
+    /// 按创建时间倒序
+    #[default]
+    Recent,
+    /// 按使用频率和最近使用时间（frecency）排序
+    Frecency,
+}
+
+impl HistorySort {
+    /// ORDER BY 子句
+    fn order_by(self) -> String {
+        match self {
+            HistorySort::Recent => "createTime DESC".to_string(),
+            HistorySort::Frecency => format!("{} DESC, createTime DESC", FRECENCY_SCORE),
+        }
+    }
+}
+
+/// 历史记录（CutItems 表）
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+#[sqlx(rename_all = "camelCase")]
+pub struct CutItem {
+    pub id: String,
+    pub content: String,
+    pub create_time: String,
+    /// 来源选区（clipboard / primary）
+    pub selection: String,
+    /// 相同内容被记录的次数
+    pub copy_count: i64,
+    /// 通过应用复制或粘贴的次数
+    pub paste_count: i64,
+    /// 最近一次通过应用复制或粘贴的时间
+    pub last_used_at: Option<String>,
+    /// 移到回收站的时间，未删除时为 None
+    pub deleted_at: Option<String>,
+    /// 内容预览（前 PREVIEW_CHARS 个字符）
+    pub preview: Option<String>,
+    /// 完整内容的字符数
+    pub length: Option<i64>,
+    /// 完整内容是否压缩存储
+    pub compressed: bool,
+}
+
+/// 分组（Groups 表）
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+#[sqlx(rename_all = "camelCase")]
+pub struct Group {
+    pub id: String,
+    pub name: String,
+    pub create_time: String,
+}
+
+/// 分组内容（GroupItems 表）
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+#[sqlx(rename_all = "camelCase")]
+pub struct GroupItem {
+    pub id: String,
+    pub group_id: String,
+    pub content: String,
+    pub title: Option<String>,
+    pub create_time: String,
+}
+
+/// 图片历史记录（ImageItems 表）
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+#[sqlx(rename_all = "camelCase")]
+pub struct ImageItem {
+    pub id: String,
+    pub content: String,
+    pub width: Option<i64>,
+    pub height: Option<i64>,
+    pub size: Option<i64>,
+    pub create_time: String,
+    pub ocr_text: Option<String>,
+    /// 编辑生成的图片指向原图
+    pub parent_id: Option<String>,
+    /// 编码格式（png / webp / jpeg）
+    pub format: Option<String>,
+    /// 编码后的字节数
+    pub byte_size: Option<i64>,
+    /// 移到回收站的时间，未删除时为 None
+    pub deleted_at: Option<String>,
+}
+
+/// 移到回收站或从回收站恢复的记录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrashedItem {
+    pub id: String,
+    pub item_type: ItemType,
+}
+
+/// 获取 sql 插件中已加载的 SQLite 连接池
+///
+/// 连接池由 tauri.conf.json 中的 preload 配置在启动时创建，
+/// 与前端共用同一个连接池，避免出现数据库锁冲突
+pub async fn pool<R: Runtime>(app: &AppHandle<R>) -> AppResult<SqlitePool> {
+    let instances = app
+        .try_state::<DbInstances>()
+        .ok_or_else(|| AppError::Database("Database plugin is not initialized".to_string()))?;
+    let instances = instances.0.read().await;
+
+    match instances.get(DB_URL) {
+        Some(DbPool::Sqlite(pool)) => Ok(pool.clone()),
+        _ => Err(AppError::Database(format!("Database {} is not loaded", DB_URL))),
+    }
+}
+
+/// 生成与前端一致的时间字符串（ISO 8601，毫秒精度，UTC）
+pub fn now_string() -> String {
+    chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, true)
+}
+
+/// 计算文本内容的哈希（SHA-256 十六进制），用于识别重复内容
+pub fn content_hash(content: &str) -> String {
+    format!("{:x}", Sha256::digest(content.as_bytes()))
+}
+
+/// 文本在 CutItems（以及 ItemRevisions）表中的存储形式
+pub(crate) struct StoredText {
+    /// content 列：未压缩时为完整内容，压缩时为预览
+    pub(crate) content: String,
+    /// contentBlob 列：zstd 压缩的完整内容
+    pub(crate) blob: Option<Vec<u8>>,
+    pub(crate) preview: String,
+    pub(crate) length: i64,
+}
+
+impl StoredText {
+    /// 超过阈值（字节）的内容压缩存储
+    pub(crate) fn new(text: &str, compress_threshold: usize) -> AppResult<Self> {
+        let preview = preview_of(text);
+        let length = text.chars().count() as i64;
+        if text.len() <= compress_threshold {
+            return Ok(Self { content: text.to_string(), blob: None, preview, length });
+        }
+
+        let blob = zstd::encode_all(text.as_bytes(), ZSTD_LEVEL)
+            .map_err(|e| AppError::Internal(format!("Failed to compress content: {}", e)))?;
+        Ok(Self { content: preview.clone(), blob: Some(blob), preview, length })
+    }
+}
+
+/// 内容预览（前 PREVIEW_CHARS 个字符）
+pub fn preview_of(text: &str) -> String {
+    text.chars().take(PREVIEW_CHARS).collect()
+}
+
+/// 由 content 和 contentBlob 列得到完整内容
+pub fn full_content(content: String, blob: Option<Vec<u8>>) -> AppResult<String> {
+    let Some(blob) = blob else {
+        return Ok(content);
+    };
+    let bytes = zstd::decode_all(blob.as_slice())
+        .map_err(|e| AppError::Internal(format!("Failed to decompress content: {}", e)))?;
+    String::from_utf8(bytes).map_err(|e| AppError::Internal(format!("Invalid compressed content: {}", e)))
+}
+
+/// 新增一条文本历史记录，超过 compress_threshold 字节的内容压缩存储
+///
+/// 同一选区已存在相同内容时不再新增，而是把已有记录的创建时间更新为当前时间并增加记录次数，返回更新后的记录；
+/// 已有记录在回收站中时同时恢复。剪切板和鼠标选区的相同内容分别记录
+pub async fn insert_cut_item(
+    pool: &SqlitePool,
+    content: &str,
+    selection: &str,
+    compress_threshold: usize,
+) -> AppResult<CutItem> {
+    let stored = StoredText::new(content, compress_threshold)?;
+    let sql = format!(
+        "INSERT INTO CutItems (id, content, createTime, selection, contentHash, contentBlob, preview, length)
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+         ON CONFLICT(selection, contentHash) DO UPDATE SET createTime = excluded.createTime, copyCount = copyCount + 1, deletedAt = NULL
+         RETURNING {}",
+        CUT_ITEM_COLUMNS
+    );
+
+    sqlx::query_as::<_, CutItem>(&sql)
+        .bind(uuid::Uuid::new_v4().to_string())
+        .bind(&stored.content)
+        .bind(now_string())
+        .bind(selection)
+        .bind(content_hash(content))
+        .bind(&stored.blob)
+        .bind(&stored.preview)
+        .bind(stored.length)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| AppError::Database(format!("Failed to insert item: {}", e)))
+}
+
+/// 更新文本历史记录的内容（同时更新内容哈希和修改时间），超过 compress_threshold 字节的内容压缩存储
+///
+/// 其他记录已有相同内容时返回错误
+pub async fn update_cut_item_content(
+    conn: &mut SqliteConnection,
+    id: &str,
+    content: &str,
+    update_time: &str,
+    compress_threshold: usize,
+) -> AppResult<()> {
+    let stored = StoredText::new(content, compress_threshold)?;
+    let result = sqlx::query(
+        "UPDATE CutItems SET content = ?, contentHash = ?, contentBlob = ?, preview = ?, length = ?, updateTime = ?
+         WHERE id = ?",
+    )
+    .bind(&stored.content)
+    .bind(content_hash(content))
+    .bind(&stored.blob)
+    .bind(&stored.preview)
+    .bind(stored.length)
+    .bind(update_time)
+    .bind(id)
+    .execute(conn)
+    .await;
+
+    match result {
+        Ok(_) => Ok(()),
+        Err(sqlx::Error::Database(e)) if e.is_unique_violation() => {
+            Err(AppError::InvalidInput("Another history item already has this content".to_string()))
+        }
+        Err(e) => Err(AppError::Database(format!("Failed to update item: {}", e))),
+    }
+}
+
+/// 检查文本是否超过配置的最大大小，新增或修改文本历史前调用
+pub fn check_text_size(content: &str, config: &AppConfig) -> AppResult<()> {
+    if content.len() > config.max_text_bytes() {
+        tracing::warn!("Rejecting text of {} bytes, max size is {} MB", content.len(), config.max_text_size_mb);
+        return Err(AppError::InvalidInput(format!(
+            "Text of {} bytes exceeds the max size of {} MB",
+            content.len(),
+            config.max_text_size_mb
+        )));
+    }
+    Ok(())
+}
+
+/// 由后端新增一条文本历史记录：应用自动标签规则，按配置清理超出的历史，并通知前端刷新列表
+///
+/// 重复内容只会更新已有记录，事件载荷同样是该记录，前端按 ID 把它移到列表顶部；
+/// 超过配置的最大长度时不记录并返回错误
+pub async fn add_cut_item(app: &AppHandle, content: &str, selection: &str) -> AppResult<CutItem> {
+    let config = AppConfig::current(app)?;
+    check_text_size(content, &config)?;
+
+    let pool = pool(app).await?;
+    let item = insert_cut_item(&pool, content, selection, config.compress_threshold_bytes()).await?;
+
+    if let Err(e) = tags::apply_auto_tags(&pool, &item.id, ItemType::CutItem, content, &config.auto_tag_rules).await {
+        tracing::error!("Failed to apply auto tags: {}", e);
+    }
+    enforce_retention(&pool, config.max_text_history, config.max_image_history).await?;
+
+    app.emit(CUT_ITEM_ADDED_EVENT, &item)?;
+    Ok(item)
+}
+
+/// 读取文本历史（含回收站）或分组内容的完整内容
+pub async fn get_item_content(pool: &SqlitePool, id: &str) -> AppResult<String> {
+    let row: Option<(String, Option<Vec<u8>>)> = sqlx::query_as(
+        "SELECT content, contentBlob FROM CutItems WHERE id = ?1
+         UNION ALL
+         SELECT content, NULL FROM GroupItems WHERE id = ?1
+         LIMIT 1",
+    )
+    .bind(id)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| AppError::Database(format!("Failed to query item: {}", e)))?;
+
+    let (content, blob) = row.ok_or_else(|| AppError::NotFound(format!("Item not found: {}", id)))?;
+    full_content(content, blob)
+}
+
+/// 压缩存储超过阈值的未压缩文本记录（升级前的记录和同步合并的记录）和历史版本，返回压缩的条数
+pub async fn compress_large_items(pool: &SqlitePool, compress_threshold: usize) -> AppResult<u64> {
+    let rows: Vec<(String, String)> = sqlx::query_as(
+        "SELECT id, content FROM CutItems WHERE contentBlob IS NULL AND contentHash IS NOT NULL AND length(CAST(content AS BLOB)) > ?",
+    )
+    .bind(compress_threshold as i64)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| AppError::Database(format!("Failed to query items: {}", e)))?;
+
+    let mut compressed = 0;
+    for (id, content) in rows {
+        let stored = StoredText::new(&content, compress_threshold)?;
+        // 内容哈希不变，同步触发器不会把压缩当作修改
+        compressed += sqlx::query("UPDATE CutItems SET content = ?, contentBlob = ?, preview = ?, length = ? WHERE id = ?")
+            .bind(&stored.content)
+            .bind(&stored.blob)
+            .bind(&stored.preview)
+            .bind(stored.length)
+            .bind(&id)
+            .execute(pool)
+            .await
+            .map_err(|e| AppError::Database(format!("Failed to compress item: {}", e)))?
+            .rows_affected();
+    }
+
+    let revisions: Vec<(String, String)> = sqlx::query_as(
+        "SELECT id, content FROM ItemRevisions WHERE contentBlob IS NULL AND length(CAST(content AS BLOB)) > ?",
+    )
+    .bind(compress_threshold as i64)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| AppError::Database(format!("Failed to query revisions: {}", e)))?;
+
+    for (id, content) in revisions {
+        let stored = StoredText::new(&content, compress_threshold)?;
+        compressed += sqlx::query("UPDATE ItemRevisions SET content = ?, contentBlob = ? WHERE id = ?")
+            .bind(&stored.content)
+            .bind(&stored.blob)
+            .bind(&id)
+            .execute(pool)
+            .await
+            .map_err(|e| AppError::Database(format!("Failed to compress revision: {}", e)))?
+            .rows_affected();
+    }
+
+    Ok(compressed)
+}
+
+/// 为升级前的记录补全内容哈希
+///
+/// 升级迁移已按内容去重；如果补全前又记录了相同内容（已带哈希），则删除较旧的这条记录
+pub async fn backfill_content_hashes(pool: &SqlitePool) -> AppResult<()> {
+    let rows: Vec<(String, String)> = sqlx::query_as("SELECT id, content FROM CutItems WHERE contentHash IS NULL")
+        .fetch_all(pool)
+        .await
+        .map_err(|e| AppError::Database(format!("Failed to query items: {}", e)))?;
+
+    for (id, content) in rows {
+        let updated = sqlx::query("UPDATE OR IGNORE CutItems SET contentHash = ? WHERE id = ?")
+            .bind(content_hash(&content))
+            .bind(&id)
+            .execute(pool)
+            .await
+            .map_err(|e| AppError::Database(format!("Failed to update content hash: {}", e)))?;
+
+        if updated.rows_affected() == 0 {
+            tracing::debug!("Removing duplicate item {} while backfilling content hashes", id);
+            sqlx::query("DELETE FROM CutItems WHERE id = ?")
+                .bind(&id)
+                .execute(pool)
+                .await
+                .map_err(|e| AppError::Database(format!("Failed to delete item: {}", e)))?;
+        }
+    }
+
+    Ok(())
+}
+
+/// 按指定排序查询文本历史记录（不含回收站），keyword 不为空时按内容模糊匹配，limit 为 None 时不限制条数
+///
+/// 压缩存储的记录 content 列只有预览，keyword 只匹配前 PREVIEW_CHARS 个字符
+pub async fn query_cut_items(
+    pool: &SqlitePool,
+    keyword: Option<&str>,
+    limit: Option<u32>,
+    sort: HistorySort,
+) -> AppResult<Vec<CutItem>> {
+    let sql = format!(
+        "SELECT {} FROM CutItems
+         WHERE deletedAt IS NULL AND (?1 IS NULL OR content LIKE '%' || ?1 || '%')
+         ORDER BY {} LIMIT ?2",
+        CUT_ITEM_COLUMNS,
+        sort.order_by()
+    );
+    sqlx::query_as::<_, CutItem>(&sql)
+        .bind(keyword)
+        .bind(limit.map_or(-1, i64::from))
+        .fetch_all(pool)
+        .await
+        .map_err(|e| AppError::Database(format!("Failed to query items: {}", e)))
+}
+
+/// 查询一条文本历史记录（不含回收站）
+pub async fn get_cut_item(pool: &SqlitePool, id: &str) -> AppResult<CutItem> {
+    sqlx::query_as::<_, CutItem>(&format!(
+        "SELECT {} FROM CutItems WHERE id = ? AND deletedAt IS NULL",
+        CUT_ITEM_COLUMNS
+    ))
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| AppError::Database(format!("Failed to query item: {}", e)))?
+        .ok_or_else(|| AppError::NotFound(format!("Item not found: {}", id)))
+}
+
+/// 记录一次使用（通过应用复制或粘贴）：增加使用次数并更新最近使用时间
+///
+/// id 可以是文本历史或分组内容
+pub async fn record_usage(pool: &SqlitePool, id: &str) -> AppResult<()> {
+    let now = now_string();
+    let mut affected = 0;
+    for table in ["CutItems", "GroupItems"] {
+        let sql = format!("UPDATE {table} SET pasteCount = pasteCount + 1, lastUsedAt = ? WHERE id = ?");
+        affected += sqlx::query(&sql)
+            .bind(&now)
+            .bind(id)
+            .execute(pool)
+            .await
+            .map_err(|e| AppError::Database(format!("Failed to record usage: {}", e)))?
+            .rows_affected();
+    }
+
+    if affected == 0 {
+        return Err(AppError::NotFound(format!("Item not found: {}", id)));
+    }
+    Ok(())
+}
+
+/// 回收站中的文本和图片记录
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Trash {
+    pub cut_items: Vec<CutItem>,
+    pub image_items: Vec<ImageItem>,
+}
+
+/// 可以移到回收站的记录类型及所在的表
+const TRASHABLE: [ItemType; 2] = [ItemType::CutItem, ItemType::ImageItem];
+
+/// 把文本或图片记录移到回收站，返回记录类型
+pub async fn trash_item(pool: &SqlitePool, id: &str) -> AppResult<ItemType> {
+    let now = now_string();
+    for item_type in TRASHABLE {
+        let sql = format!("UPDATE {} SET deletedAt = ? WHERE id = ? AND deletedAt IS NULL", item_type.table());
+        let result = sqlx::query(&sql)
+            .bind(&now)
+            .bind(id)
+            .execute(pool)
+            .await
+            .map_err(|e| AppError::Database(format!("Failed to delete item: {}", e)))?;
+        if result.rows_affected() > 0 {
+            return Ok(item_type);
+        }
+    }
+
+    Err(AppError::NotFound(format!("Item not found: {}", id)))
+}
+
+/// 从回收站恢复文本或图片记录，返回记录类型
+pub async fn restore_trashed_item(pool: &SqlitePool, id: &str) -> AppResult<ItemType> {
+    for item_type in TRASHABLE {
+        let sql = format!("UPDATE {} SET deletedAt = NULL WHERE id = ? AND deletedAt IS NOT NULL", item_type.table());
+        let result = sqlx::query(&sql)
+            .bind(id)
+            .execute(pool)
+            .await
+            .map_err(|e| AppError::Database(format!("Failed to restore item: {}", e)))?;
+        if result.rows_affected() > 0 {
+            return Ok(item_type);
+        }
+    }
+
+    Err(AppError::NotFound(format!("Item not in trash: {}", id)))
+}
+
+/// 查询回收站中的记录，按删除时间倒序
+pub async fn list_trash(pool: &SqlitePool) -> AppResult<Trash> {
+    let cut_items = sqlx::query_as::<_, CutItem>(&format!(
+        "SELECT {} FROM CutItems WHERE deletedAt IS NOT NULL ORDER BY deletedAt DESC",
+        CUT_ITEM_COLUMNS
+    ))
+    .fetch_all(pool)
+    .await
+    .map_err(|e| AppError::Database(format!("Failed to query trash: {}", e)))?;
+
+    let image_items =
+        sqlx::query_as::<_, ImageItem>("SELECT * FROM ImageItems WHERE deletedAt IS NOT NULL ORDER BY deletedAt DESC")
+            .fetch_all(pool)
+            .await
+            .map_err(|e| AppError::Database(format!("Failed to query trash: {}", e)))?;
+
+    Ok(Trash { cut_items, image_items })
+}
+
+/// 永久删除回收站中的记录，before 不为空时只删除在该时间之前移入的记录，返回删除的条数
+pub async fn purge_trash(pool: &SqlitePool, before: Option<&str>) -> AppResult<u64> {
+    let mut purged = 0;
+    for item_type in TRASHABLE {
+        let sql = format!(
+            "DELETE FROM {} WHERE deletedAt IS NOT NULL AND (?1 IS NULL OR deletedAt < ?1)",
+            item_type.table()
+        );
+        purged += sqlx::query(&sql)
+            .bind(before)
+            .execute(pool)
+            .await
+            .map_err(|e| AppError::Database(format!("Failed to empty trash: {}", e)))?
+            .rows_affected();
+    }
+
+    Ok(purged)
+}
+
+/// 永久删除在回收站中超过保留天数的记录
+pub async fn purge_expired_trash(pool: &SqlitePool, retention_days: u32) -> AppResult<u64> {
+    let cutoff = chrono::Utc::now() - chrono::Duration::days(retention_days as i64);
+    let cutoff = cutoff.to_rfc3339_opts(chrono::SecondsFormat::Millis, true);
+    purge_trash(pool, Some(&cutoff)).await
+}
+
+/// 内部函数：执行一次数据库维护
+async fn run_maintenance(app: &AppHandle) -> AppResult<()> {
+    let config = AppConfig::current(app)?;
+    let pool = pool(app).await?;
+    let purged = purge_expired_trash(&pool, config.trash_retention_days).await?;
+    let compressed = compress_large_items(&pool, config.compress_threshold_bytes()).await?;
+    tracing::debug!("Database maintenance purged {} trashed items, compressed {} items", purged, compressed);
+    db_maintenance::auto_vacuum_if_due(&pool, &config).await?;
+    Ok(())
+}
+
+/// 定期维护数据库（清理回收站中过期的记录、压缩大文本、按配置整理数据库），启动时先执行一次
+pub fn start_maintenance(app: &AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            if let Err(e) = run_maintenance(&app).await {
+                tracing::error!("Database maintenance failed: {}", e);
+            }
+
+            tokio::time::sleep(MAINTENANCE_INTERVAL).await;
+        }
+    });
+}
+
+/// 查询全部分组
+pub async fn list_groups(pool: &SqlitePool) -> AppResult<Vec<Group>> {
+    sqlx::query_as::<_, Group>("SELECT id, name, createTime FROM Groups ORDER BY createTime ASC")
+        .fetch_all(pool)
+        .await
+        .map_err(|e| AppError::Database(format!("Failed to query groups: {}", e)))
+}
+
+/// 新增一条图片历史记录
+pub async fn insert_image_item(
+    pool: &SqlitePool,
+    image: &EncodedImage,
+    parent_id: Option<&str>,
+) -> AppResult<ImageItem> {
+    let item = ImageItem {
+        id: uuid::Uuid::new_v4().to_string(),
+        content: image.data_url.clone(),
+        width: Some(image.width as i64),
+        height: Some(image.height as i64),
+        // 与前端保持一致，记录原始RGBA数据大小
+        size: Some(image.width as i64 * image.height as i64 * 4),
+        create_time: now_string(),
+        ocr_text: None,
+        parent_id: parent_id.map(str::to_string),
+        format: Some(image.format.name().to_string()),
+        byte_size: Some(image.byte_size as i64),
+        deleted_at: None,
+    };
+
+    sqlx::query(
+        "INSERT INTO ImageItems (id, content, width, height, size, createTime, parentId, format, byteSize)
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
+    )
+    .bind(&item.id)
+    .bind(&item.content)
+    .bind(item.width)
+    .bind(item.height)
+    .bind(item.size)
+    .bind(&item.create_time)
+    .bind(&item.parent_id)
+    .bind(&item.format)
+    .bind(item.byte_size)
+    .execute(pool)
+    .await
+    .map_err(|e| AppError::Database(format!("Failed to insert image: {}", e)))?;
+
+    Ok(item)
+}
+
+/// 按保留数量永久删除最旧的文本和图片历史记录（回收站中的记录不计入数量）
+///
+/// 只删除本机的记录，不写入同步发件箱和删除记录，其他设备按各自的保留数量清理
+pub async fn enforce_retention(pool: &SqlitePool, max_text: u32, max_image: u32) -> AppResult<()> {
+    // 超出数量的记录只在本机删除，不同步到其他设备
+    let mut tx = pool.begin().await?;
+    sync::suspend_outbox(&mut tx).await?;
+
+    for (table, max) in [("CutItems", max_text), ("ImageItems", max_image)] {
+        let sql = format!(
+            "DELETE FROM {table} WHERE id IN (
+                SELECT id FROM {table} WHERE deletedAt IS NULL ORDER BY createTime DESC LIMIT -1 OFFSET ?
+             )"
+        );
+        sqlx::query(&sql)
+            .bind(max)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| AppError::Database(format!("Failed to trim {}: {}", table, e)))?;
+    }
+
+    sync::resume_outbox(&mut tx).await?;
+    tx.commit().await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::migrations;
+
+    async fn count(pool: &SqlitePool, sql: &str) -> i64 {
+        sqlx::query_scalar(sql).fetch_one(pool).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn same_text_is_kept_per_selection() {
+        let pool = migrations::migrated_pool().await;
+
+        let primary = insert_cut_item(&pool, "相同内容", SELECTION_PRIMARY, usize::MAX).await.unwrap();
+        let clipboard = insert_cut_item(&pool, "相同内容", SELECTION_CLIPBOARD, usize::MAX).await.unwrap();
+        assert_ne!(primary.id, clipboard.id);
+        assert_eq!(primary.selection, SELECTION_PRIMARY);
+        assert_eq!(clipboard.selection, SELECTION_CLIPBOARD);
+
+        // 同一选区的相同内容只增加记录次数
+        let again = insert_cut_item(&pool, "相同内容", SELECTION_CLIPBOARD, usize::MAX).await.unwrap();
+        assert_eq!(again.id, clipboard.id);
+        assert_eq!(again.copy_count, 2);
+        assert_eq!(count(&pool, "SELECT COUNT(*) FROM CutItems").await, 2);
+    }
+
+    #[tokio::test]
+    async fn large_text_is_compressed() {
+        let pool = migrations::migrated_pool().await;
+        let text = format!("{}结尾", "开头".repeat(1000));
+        let item = insert_cut_item(&pool, &text, SELECTION_CLIPBOARD, 1024).await.unwrap();
+
+        assert!(item.compressed);
+        assert_eq!(item.content, preview_of(&text));
+        assert_eq!(item.preview.as_deref(), Some(item.content.as_str()));
+        assert_eq!(item.length, Some(text.chars().count() as i64));
+        assert_eq!(get_item_content(&pool, &item.id).await.unwrap(), text);
+
+        // 搜索只匹配预览
+        let found = query_cut_items(&pool, Some("开头"), None, HistorySort::Recent).await.unwrap();
+        assert_eq!(found.len(), 1);
+        let found = query_cut_items(&pool, Some("结尾"), None, HistorySort::Recent).await.unwrap();
+        assert!(found.is_empty());
+
+        // 修改为小文本后不再压缩
+        let mut conn = pool.acquire().await.unwrap();
+        update_cut_item_content(&mut conn, &item.id, "结尾", &now_string(), 1024).await.unwrap();
+        drop(conn);
+        let updated = get_cut_item(&pool, &item.id).await.unwrap();
+        assert!(!updated.compressed);
+        assert_eq!(updated.content, "结尾");
+    }
+
+    #[tokio::test]
+    async fn existing_large_text_is_compressed_later() {
+        let pool = migrations::migrated_pool().await;
+        let text = "旧记录".repeat(1000);
+        let item = insert_cut_item(&pool, &text, SELECTION_CLIPBOARD, usize::MAX).await.unwrap();
+        assert!(!item.compressed);
+        sqlx::query("DELETE FROM SyncOutbox").execute(&pool).await.unwrap();
+
+        assert_eq!(compress_large_items(&pool, 1024).await.unwrap(), 1);
+        assert!(get_cut_item(&pool, &item.id).await.unwrap().compressed);
+        assert_eq!(get_item_content(&pool, &item.id).await.unwrap(), text);
+        // 内容没有变化，不需要同步
+        assert_eq!(count(&pool, "SELECT COUNT(*) FROM SyncOutbox").await, 0);
+        assert_eq!(compress_large_items(&pool, 1024).await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn frecency_prefers_frequently_used_items() {
+        let pool = migrations::migrated_pool().await;
+        let mut ids = Vec::new();
+        for (content, days_ago) in [("常用", 30), ("新记录", 0), ("旧记录", 10)] {
+            let item = insert_cut_item(&pool, content, SELECTION_CLIPBOARD, usize::MAX).await.unwrap();
+            sqlx::query("UPDATE CutItems SET createTime = strftime('%Y-%m-%dT%H:%M:%fZ', 'now', ?) WHERE id = ?")
+                .bind(format!("-{} days", days_ago))
+                .bind(&item.id)
+                .execute(&pool)
+                .await
+                .unwrap();
+            ids.push(item.id);
+        }
+        for _ in 0..5 {
+            record_usage(&pool, &ids[0]).await.unwrap();
+        }
+
+        let contents = |items: Vec<CutItem>| items.into_iter().map(|item| item.content).collect::<Vec<_>>();
+        let recent = query_cut_items(&pool, None, None, HistorySort::Recent).await.unwrap();
+        assert_eq!(contents(recent), vec!["新记录", "旧记录", "常用"]);
+
+        // 最近多次使用的旧记录排在前面，没有使用过的记录按创建时间衰减
+        let frecency = query_cut_items(&pool, None, None, HistorySort::Frecency).await.unwrap();
+        assert_eq!(contents(frecency), vec!["常用", "新记录", "旧记录"]);
+        let top = query_cut_items(&pool, None, Some(1), HistorySort::Frecency).await.unwrap();
+        assert_eq!(top.len(), 1);
+        assert_eq!(top[0].id, ids[0]);
+    }
+
+    #[tokio::test]
+    async fn retention_is_not_synced() {
+        let pool = migrations::migrated_pool().await;
+        for content in ["第一条", "第二条", "第三条"] {
+            insert_cut_item(&pool, content, SELECTION_CLIPBOARD, usize::MAX).await.unwrap();
+        }
+        sqlx::query("DELETE FROM SyncOutbox").execute(&pool).await.unwrap();
+
+        enforce_retention(&pool, 1, 1).await.unwrap();
+        assert_eq!(count(&pool, "SELECT COUNT(*) FROM CutItems").await, 1);
+        assert_eq!(count(&pool, "SELECT COUNT(*) FROM SyncOutbox").await, 0);
+        assert_eq!(count(&pool, "SELECT COUNT(*) FROM SyncTombstones").await, 0);
+
+        // 之后本机的删除照常同步
+        sqlx::query("DELETE FROM CutItems").execute(&pool).await.unwrap();
+        assert_eq!(count(&pool, "SELECT COUNT(*) FROM SyncOutbox").await, 1);
+    }
+}
@@ -0,0 +1,150 @@
+use serde::ser::SerializeStruct;
+use serde::{Serialize, Serializer};
+use std::fmt;
+
+/// 字段级校验错误
+#[derive(Debug, Clone, Serialize)]
+pub struct FieldError {
+    /// 字段名
+    pub field: &'static str,
+    /// 本地化的错误描述
+    pub message: String,
+}
+
+/// 应用统一错误类型
+///
+/// 序列化到前端为 `{ code, message, detail }`：
+/// `code` 为稳定的机器可读错误码，`message` 为本地化的错误描述，`detail` 为原始错误信息，
+/// 校验错误额外带有 `fields` 字段级错误列表
+#[derive(Debug)]
+pub enum AppError {
+    /// 文件读写失败
+    Io(String),
+    /// 配置读取、解析或保存失败
+    Config(String),
+    /// 字段校验失败
+    Validation(Vec<FieldError>),
+    /// 数据库操作失败
+    Database(String),
+    /// 剪切板读写失败
+    Clipboard(String),
+    /// 图片解码、处理或编码失败
+    Image(String),
+    /// 图片文字识别失败
+    Ocr(String),
+    /// 记录不存在
+    NotFound(String),
+    /// 参数不合法
+    InvalidInput(String),
+    /// 系统功能（自启动、快捷键、按键模拟等）调用失败
+    System(String),
+    /// 其他内部错误
+    Internal(String),
+}
+
+pub type AppResult<T> = Result<T, AppError>;
+
+impl AppError {
+    /// 稳定的机器可读错误码
+    pub fn code(&self) -> &'static str {
+        match self {
+            AppError::Io(_) => "io",
+            AppError::Config(_) => "config",
+            AppError::Validation(_) => "validation",
+            AppError::Database(_) => "database",
+            AppError::Clipboard(_) => "clipboard",
+            AppError::Image(_) => "image",
+            AppError::Ocr(_) => "ocr",
+            AppError::NotFound(_) => "not_found",
+            AppError::InvalidInput(_) => "invalid_input",
+            AppError::System(_) => "system",
+            AppError::Internal(_) => "internal",
+        }
+    }
+
+    /// 本地化的错误描述
+    pub fn message(&self) -> &'static str {
+        match self {
+            AppError::Io(_) => "文件读写失败",
+            AppError::Config(_) => "配置错误",
+            AppError::Validation(_) => "参数校验失败",
+            AppError::Database(_) => "数据库操作失败",
+            AppError::Clipboard(_) => "剪切板操作失败",
+            AppError::Image(_) => "图片处理失败",
+            AppError::Ocr(_) => "文字识别失败",
+            AppError::NotFound(_) => "记录不存在",
+            AppError::InvalidInput(_) => "参数不合法",
+            AppError::System(_) => "系统功能调用失败",
+            AppError::Internal(_) => "内部错误",
+        }
+    }
+
+    /// 原始错误信息
+    pub fn detail(&self) -> String {
+        match self {
+            AppError::Validation(fields) => fields
+                .iter()
+                .map(|error| format!("{}: {}", error.field, error.message))
+                .collect::<Vec<_>>()
+                .join("; "),
+            AppError::Io(detail)
+            | AppError::Config(detail)
+            | AppError::Database(detail)
+            | AppError::Clipboard(detail)
+            | AppError::Image(detail)
+            | AppError::Ocr(detail)
+            | AppError::NotFound(detail)
+            | AppError::InvalidInput(detail)
+            | AppError::System(detail)
+            | AppError::Internal(detail) => detail.clone(),
+        }
+    }
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.message(), self.detail())
+    }
+}
+
+impl std::error::Error for AppError {}
+
+impl Serialize for AppError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("AppError", 4)?;
+        state.serialize_field("code", self.code())?;
+        state.serialize_field("message", self.message())?;
+        state.serialize_field("detail", &self.detail())?;
+        if let AppError::Validation(fields) = self {
+            state.serialize_field("fields", fields)?;
+        }
+        state.end()
+    }
+}
+
+impl From<std::io::Error> for AppError {
+    fn from(e: std::io::Error) -> Self {
+        AppError::Io(e.to_string())
+    }
+}
+
+impl From<sqlx::Error> for AppError {
+    fn from(e: sqlx::Error) -> Self {
+        match e {
+            sqlx::Error::RowNotFound => AppError::NotFound(e.to_string()),
+            e => AppError::Database(e.to_string()),
+        }
+    }
+}
+
+impl From<tauri::Error> for AppError {
+    fn from(e: tauri::Error) -> Self {
+        AppError::Internal(e.to_string())
+    }
+}
+
+impl From<tokio::task::JoinError> for AppError {
+    fn from(e: tokio::task::JoinError) -> Self {
+        AppError::Internal(format!("Task join error: {}", e))
+    }
+}
@@ -0,0 +1,295 @@
+//! 本地脚本接口
+//!
+//! 通过 Unix 域套接字（应用数据目录下的 cutctl.sock，Windows 上为命名管道）
+//! 提供 JSON-RPC 2.0 服务，每行一个请求、每行一个响应，供 `cutctl` 和其他脚本调用
+
+use interprocess::local_socket::Name;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// 应用标识（与 tauri.conf.json 中的 identifier 保持一致，由测试检查）
+///
+/// cutctl 没有 AppHandle，需要据此找到应用数据目录中的套接字
+pub const APP_IDENTIFIER: &str = "com.sallai.cut";
+
+/// 套接字文件名
+#[cfg(unix)]
+pub const SOCKET_FILE_NAME: &str = "cutctl.sock";
+
+/// 命名管道名（Windows 命名管道不能放在普通目录中）
+#[cfg(windows)]
+pub const PIPE_NAME: &str = "com.sallai.cut.cutctl";
+
+/// JSON-RPC 错误码
+pub mod error_code {
+    pub const PARSE_ERROR: i64 = -32700;
+    pub const INVALID_REQUEST: i64 = -32600;
+    pub const METHOD_NOT_FOUND: i64 = -32601;
+    pub const INVALID_PARAMS: i64 = -32602;
+    /// 应用错误，data 为 AppError 序列化结果
+    pub const APP_ERROR: i64 = -32000;
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RpcRequest {
+    pub jsonrpc: String,
+    #[serde(default)]
+    pub id: Value,
+    pub method: String,
+    #[serde(default)]
+    pub params: Value,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RpcResponse {
+    pub jsonrpc: String,
+    pub id: Value,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub result: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<RpcError>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RpcError {
+    pub code: i64,
+    pub message: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<Value>,
+}
+
+/// 默认应用数据目录（与 Tauri 的 app_data_dir 一致），供没有 AppHandle 的客户端使用
+pub fn default_app_data_dir() -> Option<PathBuf> {
+    dirs::data_dir().map(|dir| dir.join(APP_IDENTIFIER))
+}
+
+/// 本地连接地址
+#[cfg(unix)]
+pub fn endpoint(app_data_dir: &Path) -> io::Result<Name<'static>> {
+    use interprocess::local_socket::{GenericFilePath, ToFsName};
+
+    app_data_dir.join(SOCKET_FILE_NAME).to_fs_name::<GenericFilePath>()
+}
+
+#[cfg(windows)]
+pub fn endpoint(_app_data_dir: &Path) -> io::Result<Name<'static>> {
+    use interprocess::local_socket::{GenericNamespaced, ToNsName};
+
+    PIPE_NAME.to_ns_name::<GenericNamespaced>()
+}
+
+#[cfg(desktop)]
+mod server {
+    use interprocess::local_socket::tokio::{prelude::*, Stream};
+    use interprocess::local_socket::ListenerOptions;
+    use serde::de::DeserializeOwned;
+    use serde::Deserialize;
+    use serde_json::Value;
+    use tauri::{AppHandle, Emitter, Manager};
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+    use super::{error_code, RpcError, RpcRequest, RpcResponse};
+    use crate::config::AppConfig;
+    use crate::db;
+    use crate::error::{AppError, AppResult};
+    use crate::utils::clipboard;
+
+    #[derive(Deserialize)]
+    struct ListParams {
+        limit: Option<u32>,
+    }
+
+    #[derive(Deserialize)]
+    struct SearchParams {
+        keyword: String,
+        limit: Option<u32>,
+    }
+
+    #[derive(Deserialize)]
+    struct IdParams {
+        id: String,
+    }
+
+    #[derive(Deserialize)]
+    struct AddParams {
+        content: String,
+    }
+
+    impl From<AppError> for RpcError {
+        fn from(e: AppError) -> Self {
+            RpcError {
+                code: error_code::APP_ERROR,
+                message: e.to_string(),
+                data: serde_json::to_value(&e).ok(),
+            }
+        }
+    }
+
+    fn rpc_error(code: i64, message: impl Into<String>) -> RpcError {
+        RpcError { code, message: message.into(), data: None }
+    }
+
+    /// 内部函数：解析方法参数，缺省参数按空对象处理
+    fn parse_params<T: DeserializeOwned>(params: Value) -> Result<T, RpcError> {
+        let params = if params.is_null() { Value::Object(Default::default()) } else { params };
+        serde_json::from_value(params)
+            .map_err(|e| rpc_error(error_code::INVALID_PARAMS, format!("Invalid params: {}", e)))
+    }
+
+    fn to_result<T: serde::Serialize>(value: AppResult<T>) -> Result<Value, RpcError> {
+        let value = value?;
+        serde_json::to_value(value)
+            .map_err(|e| RpcError::from(AppError::Internal(format!("Failed to serialize result: {}", e))))
+    }
+
+    /// 内部函数：执行一个方法调用
+    async fn dispatch(app: &AppHandle, method: &str, params: Value) -> Result<Value, RpcError> {
+        let pool = db::pool(app).await?;
+
+        match method {
+            "list" => {
+                let ListParams { limit } = parse_params(params)?;
+                let sort = AppConfig::current(app)?.history_sort;
+                to_result(db::query_cut_items(&pool, None, limit, sort).await)
+            }
+            "search" => {
+                let SearchParams { keyword, limit } = parse_params(params)?;
+                let sort = AppConfig::current(app)?.history_sort;
+                to_result(db::query_cut_items(&pool, Some(&keyword), limit, sort).await)
+            }
+            "get" => {
+                let IdParams { id } = parse_params(params)?;
+                let mut item = db::get_cut_item(&pool, &id).await?;
+                if item.compressed {
+                    item.content = db::get_item_content(&pool, &id).await?;
+                }
+                to_result(Ok(item))
+            }
+            "add" => {
+                let AddParams { content } = parse_params(params)?;
+                to_result(db::add_cut_item(app, &content, db::SELECTION_CLIPBOARD).await)
+            }
+            "copy" => {
+                let IdParams { id } = parse_params(params)?;
+                // 确认是未删除的文本历史
+                db::get_cut_item(&pool, &id).await?;
+                let content = db::get_item_content(&pool, &id).await?;
+                clipboard::write_text(app, &content)?;
+                to_result(db::record_usage(&pool, &id).await)
+            }
+            "delete" => {
+                let IdParams { id } = parse_params(params)?;
+                let item_type = db::trash_item(&pool, &id).await?;
+                app.emit(db::ITEM_TRASHED_EVENT, db::TrashedItem { id, item_type }).map_err(AppError::from)?;
+                to_result(Ok(()))
+            }
+            "groups" => to_result(db::list_groups(&pool).await),
+            _ => Err(rpc_error(error_code::METHOD_NOT_FOUND, format!("Method not found: {}", method))),
+        }
+    }
+
+    /// 内部函数：处理一行请求
+    async fn handle_line(app: &AppHandle, line: &str) -> RpcResponse {
+        let (id, outcome) = match serde_json::from_str::<Value>(line) {
+            Err(e) => (Value::Null, Err(rpc_error(error_code::PARSE_ERROR, format!("Parse error: {}", e)))),
+            Ok(value) => {
+                let id = value.get("id").cloned().unwrap_or(Value::Null);
+                match serde_json::from_value::<RpcRequest>(value) {
+                    Ok(request) if request.jsonrpc == "2.0" => {
+                        (id, dispatch(app, &request.method, request.params).await)
+                    }
+                    Ok(_) => (id, Err(rpc_error(error_code::INVALID_REQUEST, "Unsupported jsonrpc version"))),
+                    Err(e) => (id, Err(rpc_error(error_code::INVALID_REQUEST, format!("Invalid request: {}", e)))),
+                }
+            }
+        };
+
+        let (result, error) = match outcome {
+            Ok(result) => (Some(result), None),
+            Err(error) => (None, Some(error)),
+        };
+        RpcResponse { jsonrpc: "2.0".to_string(), id, result, error }
+    }
+
+    /// 内部函数：处理一个客户端连接，逐行读取请求并返回响应
+    async fn handle_connection(app: AppHandle, stream: Stream) {
+        let (reader, mut writer) = stream.split();
+        let mut lines = BufReader::new(reader).lines();
+
+        while let Ok(Some(line)) = lines.next_line().await {
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let response = handle_line(&app, &line).await;
+            let mut payload = match serde_json::to_string(&response) {
+                Ok(payload) => payload,
+                Err(e) => {
+                    tracing::error!("Failed to serialize IPC response: {}", e);
+                    break;
+                }
+            };
+            payload.push('\n');
+            if writer.write_all(payload.as_bytes()).await.is_err() {
+                break;
+            }
+        }
+    }
+
+    /// 启动本地脚本接口服务
+    pub fn start(app: &AppHandle) -> AppResult<()> {
+        let app_data_dir = app
+            .path()
+            .app_data_dir()
+            .map_err(|e| AppError::Io(format!("Failed to get app data dir: {}", e)))?;
+        std::fs::create_dir_all(&app_data_dir)
+            .map_err(|e| AppError::Io(format!("Failed to create app data dir: {}", e)))?;
+
+        let name = super::endpoint(&app_data_dir)
+            .map_err(|e| AppError::System(format!("Invalid IPC endpoint: {}", e)))?;
+
+        // 清理上次异常退出残留的套接字文件
+        #[cfg(unix)]
+        let _ = std::fs::remove_file(app_data_dir.join(super::SOCKET_FILE_NAME));
+
+        let app = app.clone();
+        tauri::async_runtime::spawn(async move {
+            // 监听器需要在 tokio 运行时中创建
+            let listener = match ListenerOptions::new().name(name).create_tokio() {
+                Ok(listener) => listener,
+                Err(e) => {
+                    tracing::error!("Failed to start IPC server: {}", e);
+                    return;
+                }
+            };
+
+            loop {
+                match listener.accept().await {
+                    Ok(stream) => {
+                        tauri::async_runtime::spawn(handle_connection(app.clone(), stream));
+                    }
+                    Err(e) => tracing::error!("Failed to accept IPC connection: {}", e),
+                }
+            }
+        });
+
+        Ok(())
+    }
+}
+
+#[cfg(desktop)]
+pub use server::start;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn identifier_matches_tauri_conf() {
+        let conf: serde_json::Value =
+            serde_json::from_str(include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/tauri.conf.json"))).unwrap();
+        assert_eq!(conf["identifier"].as_str(), Some(APP_IDENTIFIER));
+    }
+}
@@ -1,93 +1,107 @@
 pub mod commands;
 pub mod utils;
 pub mod config;
-use tauri_plugin_sql::{Migration, MigrationKind};
+pub mod db;
+pub mod error;
+pub mod ipc;
+pub mod logging;
+pub mod migrations;
+pub mod sync;
+pub mod window_manager;
 mod tray;
+#[cfg(desktop)]
+mod cli;
 
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
-    let migrations = vec![
-        // 版本2 - 原始的初始表创建（必须保留，不能修改，否则数据库会报错）
-        Migration {
-            version: 2,
-            description: "create_initial_tables",
-            sql: r#"
-            CREATE TABLE  IF NOT EXISTS "CutItems" (
-                "id" UUID NOT NULL,
-                "content" TEXT NOT NULL,
-                "createTime" DATETIME NOT NULL,
-                PRIMARY KEY ("id")
-              );
+    let builder = tauri::Builder::default();
 
-            CREATE TABLE IF NOT EXISTS "Groups" (
-            "id" UUID NOT NULL,
-            "name" VARCHAR(255) NOT NULL,
-            "createTime" DATETIME NOT NULL,
-            PRIMARY KEY ("id")
-            );
+    // 单实例：重复启动时聚焦已运行的实例并转发启动参数（必须最先注册）
+    #[cfg(desktop)]
+    let builder = builder.plugin(tauri_plugin_single_instance::init(|app, args, _cwd| {
+        cli::handle_second_instance(app, &args);
+    }));
 
-            CREATE TABLE IF NOT EXISTS "GroupItems" (
-            "id" UUID NOT NULL,
-            "groupId" VARCHAR(255) NOT NULL,
-            "content" TEXT NOT NULL,
-            "title" VARCHAR(255),
-            "createTime" DATETIME NOT NULL,
-            "updateTime" DATETIME,
-            PRIMARY KEY ("id")
-            );
-
-            "#,
-            kind: MigrationKind::Up,
-        },
-        // 版本4 - 添加图片表（跳过版本3避免之前的冲突）
-        Migration {
-            version: 4,
-            description: "add_image_items_table",
-            sql: r#"
-            CREATE TABLE IF NOT EXISTS "ImageItems" (
-            "id" UUID NOT NULL,
-            "content" TEXT NOT NULL,
-            "width" INTEGER,
-            "height" INTEGER,
-            "size" INTEGER,
-            "createTime" DATETIME NOT NULL,
-            PRIMARY KEY ("id")
-            );
-            "#,
-            kind: MigrationKind::Up,
-        },
-    ];
-
-    tauri::Builder::default()
+    builder
         .plugin(tauri_plugin_process::init())
         .plugin(tauri_plugin_clipboard_manager::init())
         .plugin(tauri_plugin_positioner::init())
         .plugin(
             tauri_plugin_sql::Builder::default()
-                .add_migrations("sqlite:cut.db", migrations)
+                .add_migrations("sqlite:cut.db", migrations::all())
                 .build(),
         )
         .plugin(tauri_plugin_global_shortcut::Builder::new().build())
         .plugin(tauri_plugin_opener::init())
-        .plugin(tauri_plugin_autostart::init(tauri_plugin_autostart::MacosLauncher::LaunchAgent, Some(vec!["--flag1", "--flag2"])))
+        .plugin(tauri_plugin_autostart::init(tauri_plugin_autostart::MacosLauncher::LaunchAgent, Some(vec!["--minimized"])))
         .setup(|app| {
+            // 初始化日志（写入应用日志目录），级别由加载的配置调整
+            if let Err(e) = logging::init(app.handle()) {
+                eprintln!("Failed to init logging: {}", e);
+            }
+
+            // 加载配置到托管状态并应用（自启动、全局快捷键、历史保留数量）
+            if let Err(e) = config::init_config(app.handle()) {
+                tracing::error!("Failed to apply config: {}", e);
+            }
+
+            // 定期清理回收站
+            db::start_maintenance(app.handle());
+
+            // 为升级前的文本记录补全内容哈希
+            let db_handle = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                let result = match db::pool(&db_handle).await {
+                    Ok(pool) => db::backfill_content_hashes(&pool).await,
+                    Err(e) => Err(e),
+                };
+                if let Err(e) = result {
+                    tracing::error!("Failed to backfill content hashes: {}", e);
+                }
+            });
+
             #[cfg(all(desktop))]
             {
             let handle = app.handle();
-            tray::create_tray(handle)?;
+            if let Err(e) = tray::create_tray(handle) {
+                tracing::error!("Failed to create tray: {}", e);
+            }
+
+            // 显示/隐藏主窗口的全局快捷键
+            if let Err(e) = window_manager::register_toggle_shortcut(handle) {
+                tracing::error!("{}", e);
+            }
             
-            // 根据配置设置自启动
-            let config_result = config::AppConfig::load(&handle);
-            if let Ok(cfg) = config_result {
-                use tauri_plugin_autostart::ManagerExt;
-                let auto_launch = handle.autolaunch();
-                
-                if cfg.auto_start {
-                    let _ = auto_launch.enable();
-                } else {
-                    let _ = auto_launch.disable();
-                }
+            // 监听配置文件的外部修改
+            if let Err(e) = config::watch_config(handle) {
+                tracing::error!("Failed to watch config file: {}", e);
+            }
+
+            // 启动本地脚本接口（cutctl）
+            if let Err(e) = ipc::start(handle) {
+                tracing::error!("Failed to start IPC server: {}", e);
+            }
+
+            // 后台定时同步
+            sync::start(handle);
+
+            // 监听 X11 鼠标选区（PRIMARY）
+            #[cfg(target_os = "linux")]
+            utils::primary_selection::start(handle);
+
+            // 主窗口默认隐藏创建，按配置和启动参数决定是否显示
+            let args: Vec<String> = std::env::args().skip(1).collect();
+            let show_window = config::AppConfig::current(handle)
+                .map(|cfg| cfg.show_window_on_launch)
+                .unwrap_or(true);
+            if show_window && !cli::is_background_launch(&args) {
+                cli::run_action(handle, cli::LaunchAction::Show);
+            }
+
+            // 执行首次启动时传入的参数（--show / --toggle / --settings / --paste <n>）
+            for action in cli::parse_args(&args) {
+                cli::run_action(handle, action);
             }
             }
             Ok(())
@@ -95,13 +109,47 @@ pub fn run() {
         .invoke_handler(tauri::generate_handler![
             commands::cut_admin::test_fun,
             commands::cut_admin::get_db_path,
+            commands::cut_admin::add_text_item,
+            commands::cut_admin::list_text_items,
+            commands::cut_admin::get_item_content,
+            commands::cut_admin::enforce_history_limits,
+            commands::cut_admin::delete_item,
+            commands::cut_admin::restore_item,
+            commands::cut_admin::list_trash,
+            commands::cut_admin::empty_trash,
+            commands::db_maintenance::db_stats,
+            commands::db_maintenance::vacuum_database,
+            commands::db_maintenance::check_integrity,
             commands::image_processor::process_clipboard_image,
             commands::image_processor::calculate_image_hash,
             commands::image_processor::monitor_and_process_clipboard_image,
+            commands::image_editor::edit_image_item,
+            commands::item_merge::merge_items,
+            commands::item_merge::start_collect,
+            commands::item_merge::collect_append,
+            commands::item_merge::finish_collect,
+            commands::item_merge::cancel_collect,
+            commands::item_merge::get_collect_status,
+            commands::paste::paste_item,
+            commands::paste::record_item_usage,
+            commands::item_edit::update_item_content,
+            commands::item_edit::list_revisions,
+            commands::item_edit::restore_revision,
+            commands::tags::add_item_tag,
+            commands::tags::remove_item_tag,
+            commands::tags::get_item_tags,
+            commands::tags::list_tags,
+            commands::tags::list_items_by_tag,
+            commands::ocr::ocr_image_item,
+            commands::ocr::extract_text_from_image,
+            commands::ocr::search_image_items,
+            sync::sync_now,
             config::get_config,
-            config::save_config,
+            config::update_config,
             config::set_auto_start,
-            config::is_auto_start_enabled
+            config::is_auto_start_enabled,
+            logging::get_recent_logs,
+            logging::write_log
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
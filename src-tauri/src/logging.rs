@@ -0,0 +1,191 @@
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager, Runtime};
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::filter::LevelFilter;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{fmt, reload, Registry};
+
+use crate::error::{AppError, AppResult};
+
+/// 日志文件名前缀和后缀，按天滚动生成 cut.YYYY-MM-DD.log
+const LOG_FILE_PREFIX: &str = "cut";
+const LOG_FILE_SUFFIX: &str = "log";
+
+/// 最多保留的日志文件数（天）
+const MAX_LOG_FILES: usize = 7;
+
+/// get_recent_logs 最多返回的行数
+const MAX_RECENT_LINES: usize = 5000;
+
+/// 日志级别
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogLevel {
+    Error,
+    Warn,
+    #[default]
+    Info,
+    Debug,
+    Trace,
+}
+
+impl From<LogLevel> for LevelFilter {
+    fn from(level: LogLevel) -> Self {
+        match level {
+            LogLevel::Error => LevelFilter::ERROR,
+            LogLevel::Warn => LevelFilter::WARN,
+            LogLevel::Info => LevelFilter::INFO,
+            LogLevel::Debug => LevelFilter::DEBUG,
+            LogLevel::Trace => LevelFilter::TRACE,
+        }
+    }
+}
+
+/// 日志状态，放入 Tauri 托管状态：保持写入线程存活，并用于运行时修改日志级别
+struct Logging {
+    _guard: WorkerGuard,
+    filter: reload::Handle<LevelFilter, Registry>,
+}
+
+/// 日志目录（应用日志目录）
+pub fn log_dir<R: Runtime>(app: &AppHandle<R>) -> AppResult<PathBuf> {
+    app.path()
+        .app_log_dir()
+        .map_err(|e| AppError::Io(format!("Failed to get log dir: {}", e)))
+}
+
+/// 初始化日志（启动时最先调用一次）：输出到标准错误和日志目录下按天滚动的文件
+///
+/// 初始级别为 info，加载配置后由 set_level 调整
+pub fn init(app: &AppHandle) -> AppResult<()> {
+    let dir = log_dir(app)?;
+    fs::create_dir_all(&dir).map_err(|e| AppError::Io(format!("Failed to create log dir: {}", e)))?;
+
+    let appender = RollingFileAppender::builder()
+        .rotation(Rotation::DAILY)
+        .filename_prefix(LOG_FILE_PREFIX)
+        .filename_suffix(LOG_FILE_SUFFIX)
+        .max_log_files(MAX_LOG_FILES)
+        .build(&dir)
+        .map_err(|e| AppError::Io(format!("Failed to create log file: {}", e)))?;
+    let (writer, guard) = tracing_appender::non_blocking(appender);
+
+    let (filter, handle) = reload::Layer::new(LevelFilter::from(LogLevel::default()));
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(fmt::layer().with_writer(std::io::stderr))
+        .with(fmt::layer().with_ansi(false).with_writer(writer))
+        .try_init()
+        .map_err(|e| AppError::Internal(format!("Failed to init logging: {}", e)))?;
+
+    app.manage(Logging { _guard: guard, filter: handle });
+    install_panic_hook(dir.clone());
+    tracing::info!("Logging to {}", dir.display());
+    Ok(())
+}
+
+/// 内部函数：panic 时把信息和调用栈写入日志，再交给默认处理（输出到标准错误）
+///
+/// 日志由后台线程写入，进程随后退出时可能来不及写出，因此不经过 tracing，直接同步追加写入当天的日志文件
+fn install_panic_hook(dir: PathBuf) {
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let message = format!("Panic: {}\n{}", info, std::backtrace::Backtrace::force_capture());
+        if let Err(e) = append_panic(&dir, &message) {
+            eprintln!("Failed to write panic to log file: {}", e);
+        }
+        default_hook(info);
+    }));
+}
+
+/// 内部函数：同步追加写入当天（UTC，与按天滚动的文件名一致）的日志文件并刷新到磁盘
+fn append_panic(dir: &Path, message: &str) -> std::io::Result<()> {
+    let now = chrono::Utc::now();
+    let path = dir.join(format!("{}.{}.{}", LOG_FILE_PREFIX, now.format("%Y-%m-%d"), LOG_FILE_SUFFIX));
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(
+        file,
+        "{} ERROR {}",
+        now.to_rfc3339_opts(chrono::SecondsFormat::Micros, true),
+        message
+    )?;
+    file.sync_all()
+}
+
+/// 修改日志级别，日志未初始化时不做处理
+pub fn set_level(app: &AppHandle, level: LogLevel) -> AppResult<()> {
+    let Some(logging) = app.try_state::<Logging>() else {
+        return Ok(());
+    };
+    logging
+        .filter
+        .modify(|filter| *filter = level.into())
+        .map_err(|e| AppError::Internal(format!("Failed to set log level: {}", e)))
+}
+
+/// 内部函数：日志目录中的日志文件，按日期从新到旧
+fn log_files(dir: &Path) -> AppResult<Vec<PathBuf>> {
+    let mut files: Vec<PathBuf> = fs::read_dir(dir)
+        .map_err(|e| AppError::Io(format!("Failed to read log dir: {}", e)))?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| {
+                    name.starts_with(&format!("{}.", LOG_FILE_PREFIX))
+                        && name.ends_with(&format!(".{}", LOG_FILE_SUFFIX))
+                })
+        })
+        .collect();
+    // 文件名中的日期为 YYYY-MM-DD，按名称排序即按日期排序
+    files.sort();
+    files.reverse();
+    Ok(files)
+}
+
+/// 读取最近的 n 行日志（最多 5000 行），按时间顺序返回，用于附在问题反馈中
+#[tauri::command]
+pub fn get_recent_logs(app: AppHandle, n: usize) -> AppResult<Vec<String>> {
+    let n = n.min(MAX_RECENT_LINES);
+    let mut lines = Vec::with_capacity(n);
+
+    for file in log_files(&log_dir(&app)?)? {
+        if lines.len() >= n {
+            break;
+        }
+        let content = fs::read(&file).map_err(|e| AppError::Io(format!("Failed to read log file: {}", e)))?;
+        let content = String::from_utf8_lossy(&content);
+        lines.extend(content.lines().rev().take(n - lines.len()).map(str::to_string));
+    }
+
+    lines.reverse();
+    Ok(lines)
+}
+
+/// 前端写入日志（目标为 frontend），前端的错误和后端日志记录在同一个文件中
+#[tauri::command]
+pub fn write_log(level: LogLevel, message: String) {
+    match level {
+        LogLevel::Error => tracing::error!(target: "frontend", "{}", message),
+        LogLevel::Warn => tracing::warn!(target: "frontend", "{}", message),
+        LogLevel::Info => tracing::info!(target: "frontend", "{}", message),
+        LogLevel::Debug => tracing::debug!(target: "frontend", "{}", message),
+        LogLevel::Trace => tracing::trace!(target: "frontend", "{}", message),
+    }
+}
+
+/// 用系统文件管理器打开日志目录
+pub fn open_log_dir<R: Runtime>(app: &AppHandle<R>) -> AppResult<()> {
+    use tauri_plugin_opener::OpenerExt;
+
+    let dir = log_dir(app)?;
+    fs::create_dir_all(&dir).map_err(|e| AppError::Io(format!("Failed to create log dir: {}", e)))?;
+    app.opener()
+        .open_path(dir.to_string_lossy(), None::<&str>)
+        .map_err(|e| AppError::System(format!("Failed to open log dir: {}", e)))
+}
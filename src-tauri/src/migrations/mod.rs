@@ -0,0 +1,85 @@
+//! 数据库迁移，每个版本一个文件（vNN_描述.rs）
+//!
+//! 已发布的迁移 SQL 不能修改：tauri-plugin-sql 会校验已执行迁移的校验和，修改后启动时报错。
+//! 需要调整表结构时添加新的版本。版本3 曾被占用过，已跳过。
+
+use tauri_plugin_sql::Migration;
+
+mod v02_create_initial_tables;
+mod v04_add_image_items_table;
+mod v05_add_image_ocr_text;
+mod v06_add_image_parent_id;
+mod v07_add_image_format_and_byte_size;
+mod v08_add_cut_item_selection;
+mod v09_add_sync_tables;
+mod v10_add_usage_tracking;
+mod v11_dedupe_cut_items;
+mod v12_add_tags;
+mod v13_add_soft_delete;
+mod v14_add_item_revisions;
+mod v15_add_compressed_content;
+mod v16_add_app_state;
+mod v17_add_indexes_and_group_fk;
+mod v18_add_group_sync;
+mod v19_add_trash_sync;
+mod v20_separate_selection_duplicates;
+mod v21_compress_revisions;
+
+#[cfg(test)]
+mod tests;
+
+/// 测试用：打开内存数据库，只用一个连接（每个连接的内存数据库是独立的）
+#[cfg(test)]
+pub(crate) async fn memory_pool() -> sqlx::SqlitePool {
+    sqlx::sqlite::SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect("sqlite::memory:")
+        .await
+        .expect("failed to open in-memory database")
+}
+
+/// 测试用：和 tauri-plugin-sql 一样，在事务中按顺序执行迁移
+#[cfg(test)]
+pub(crate) async fn apply(pool: &sqlx::SqlitePool, migrations: &[Migration]) {
+    for migration in migrations {
+        let mut tx = pool.begin().await.unwrap();
+        sqlx::raw_sql(migration.sql)
+            .execute(&mut *tx)
+            .await
+            .unwrap_or_else(|e| panic!("migration {} ({}) failed: {}", migration.version, migration.description, e));
+        tx.commit().await.unwrap();
+    }
+}
+
+/// 测试用：执行了全部迁移的内存数据库
+#[cfg(test)]
+pub(crate) async fn migrated_pool() -> sqlx::SqlitePool {
+    let pool = memory_pool().await;
+    apply(&pool, &all()).await;
+    pool
+}
+
+/// 全部迁移，按版本升序
+pub fn all() -> Vec<Migration> {
+    vec![
+        v02_create_initial_tables::migration(),
+        v04_add_image_items_table::migration(),
+        v05_add_image_ocr_text::migration(),
+        v06_add_image_parent_id::migration(),
+        v07_add_image_format_and_byte_size::migration(),
+        v08_add_cut_item_selection::migration(),
+        v09_add_sync_tables::migration(),
+        v10_add_usage_tracking::migration(),
+        v11_dedupe_cut_items::migration(),
+        v12_add_tags::migration(),
+        v13_add_soft_delete::migration(),
+        v14_add_item_revisions::migration(),
+        v15_add_compressed_content::migration(),
+        v16_add_app_state::migration(),
+        v17_add_indexes_and_group_fk::migration(),
+        v18_add_group_sync::migration(),
+        v19_add_trash_sync::migration(),
+        v20_separate_selection_duplicates::migration(),
+        v21_compress_revisions::migration(),
+    ]
+}
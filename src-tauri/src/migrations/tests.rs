@@ -0,0 +1,166 @@
+use sqlx::sqlite::SqlitePool;
+
+use super::{all, apply, memory_pool};
+
+/// 内部函数：查询某类数据库对象（table、index、trigger）的名称
+async fn schema_names(pool: &SqlitePool, kind: &str) -> Vec<String> {
+    sqlx::query_scalar("SELECT name FROM sqlite_master WHERE type = ? ORDER BY name")
+        .bind(kind)
+        .fetch_all(pool)
+        .await
+        .unwrap()
+}
+
+async fn count(pool: &SqlitePool, sql: &str) -> i64 {
+    sqlx::query_scalar(sql).fetch_one(pool).await.unwrap()
+}
+
+/// 内部函数：检查数据库完整性和外键
+async fn assert_consistent(pool: &SqlitePool) {
+    let integrity: Vec<String> = sqlx::query_scalar("PRAGMA integrity_check").fetch_all(pool).await.unwrap();
+    assert_eq!(integrity, vec!["ok".to_string()]);
+
+    let violations = sqlx::query("PRAGMA foreign_key_check").fetch_all(pool).await.unwrap();
+    assert!(violations.is_empty(), "{} foreign key violations", violations.len());
+}
+
+#[test]
+fn versions_are_ascending() {
+    let versions: Vec<i64> = all().iter().map(|m| m.version).collect();
+    assert!(versions.windows(2).all(|w| w[0] < w[1]), "versions not ascending: {:?}", versions);
+}
+
+#[tokio::test]
+async fn applies_to_empty_database() {
+    let pool = memory_pool().await;
+    apply(&pool, &all()).await;
+
+    let tables = schema_names(&pool, "table").await;
+    for table in [
+        "AppState",
+        "CutItems",
+        "GroupItems",
+        "Groups",
+        "ImageItems",
+        "ItemRevisions",
+        "ItemTags",
+        "SyncOutbox",
+        "SyncState",
+        "SyncTombstones",
+        "Tags",
+    ] {
+        assert!(tables.iter().any(|t| t == table), "missing table {}", table);
+    }
+    assert!(!tables.iter().any(|t| t == "GroupItems_new"));
+
+    let indexes = schema_names(&pool, "index").await;
+    for index in ["CutItems_createTime", "CutItems_selection_contentHash", "GroupItems_groupId", "ImageItems_createTime"] {
+        assert!(indexes.iter().any(|i| i == index), "missing index {}", index);
+    }
+
+    // 重建 GroupItems 后触发器需要重新创建
+    let triggers = schema_names(&pool, "trigger").await;
+    for trigger in [
+        "CutItems_sync_trash",
+        "GroupItems_delete_revisions",
+        "GroupItems_delete_tags",
+        "GroupItems_sync_delete",
+        "GroupItems_sync_insert",
+        "GroupItems_sync_update",
+        "Groups_sync_delete",
+        "Groups_sync_update",
+    ] {
+        assert!(triggers.iter().any(|t| t == trigger), "missing trigger {}", trigger);
+    }
+
+    let foreign_keys: Vec<(String, String, String)> =
+        sqlx::query_as(r#"SELECT "table", "from", "on_delete" FROM pragma_foreign_key_list('GroupItems')"#)
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+    assert_eq!(foreign_keys, vec![("Groups".to_string(), "groupId".to_string(), "CASCADE".to_string())]);
+
+    assert_consistent(&pool).await;
+}
+
+#[tokio::test]
+async fn applies_to_populated_database() {
+    let migrations = all();
+    let split = migrations.iter().position(|m| m.version == 5).unwrap();
+    let (before, after) = migrations.split_at(split);
+
+    // 已发布的安装停留在版本4，在版本4 的表结构下写入数据：重复文本、正常和孤立的分组内容、图片
+    let pool = memory_pool().await;
+    apply(&pool, before).await;
+    sqlx::raw_sql(
+        r#"
+        INSERT INTO "CutItems" ("id", "content", "createTime") VALUES
+        ('c1', 'hello', '2024-01-01T00:00:00.000Z'),
+        ('c2', 'hello', '2024-01-02T00:00:00.000Z'),
+        ('c3', 'world', '2024-01-03T00:00:00.000Z');
+
+        INSERT INTO "Groups" ("id", "name", "createTime") VALUES ('g1', '工作', '2024-01-01T00:00:00.000Z');
+
+        INSERT INTO "GroupItems" ("id", "groupId", "content", "title", "createTime") VALUES
+        ('gi1', 'g1', '会议纪要', '纪要', '2024-01-01T00:00:00.000Z'),
+        ('gi2', 'missing', '孤立内容', NULL, '2024-01-02T00:00:00.000Z');
+
+        INSERT INTO "ImageItems" ("id", "content", "width", "height", "size", "createTime")
+        VALUES ('i1', 'data:image/png;base64,AAAA', 1, 1, 4, '2024-01-01T00:00:00.000Z');
+        "#,
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+    apply(&pool, after).await;
+
+    // 重复文本合并为最新的一条并累计次数
+    let hello: (String, i64) = sqlx::query_as(r#"SELECT "id", "copyCount" FROM "CutItems" WHERE "content" = 'hello'"#)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(hello, ("c2".to_string(), 2));
+    assert_eq!(count(&pool, r#"SELECT COUNT(*) FROM "CutItems""#).await, 2);
+
+    let world: (String, i64) = sqlx::query_as(r#"SELECT "preview", "length" FROM "CutItems" WHERE "id" = 'c3'"#)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(world, ("world".to_string(), 5));
+
+    // 版本11 合并重复文本时触发版本9 的删除触发器，被合并的记录进入发件箱并留下删除记录；
+    // 首次同步导出快照时清空发件箱，被合并的记录在其他设备上本来也是重复内容
+    let outbox: Vec<(String, String, String)> =
+        sqlx::query_as(r#"SELECT "entity", "entityId", "op" FROM "SyncOutbox" ORDER BY "seq""#)
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+    assert_eq!(outbox, vec![("cut_item".to_string(), "c1".to_string(), "delete".to_string())]);
+    let tombstones: Vec<String> = sqlx::query_scalar(r#"SELECT "id" FROM "SyncTombstones""#)
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+    assert_eq!(tombstones, vec!["c1".to_string()]);
+
+    // 分组内容全部保留，孤立的内容归入恢复的分组
+    assert_eq!(count(&pool, r#"SELECT COUNT(*) FROM "GroupItems""#).await, 2);
+    let recovered: String = sqlx::query_scalar(r#"SELECT "name" FROM "Groups" WHERE "id" = 'missing'"#)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(recovered, "恢复的分组");
+    assert_eq!(count(&pool, r#"SELECT COUNT(*) FROM "ImageItems""#).await, 1);
+    assert_consistent(&pool).await;
+
+    // 外键生效：不能添加不存在分组的内容，删除分组时一并删除内容并记录删除
+    let orphan = sqlx::query(
+        r#"INSERT INTO "GroupItems" ("id", "groupId", "content", "createTime") VALUES ('gi3', 'nope', 'x', '2024-01-03T00:00:00.000Z')"#,
+    )
+    .execute(&pool)
+    .await;
+    assert!(orphan.is_err());
+
+    sqlx::query(r#"DELETE FROM "Groups" WHERE "id" = 'g1'"#).execute(&pool).await.unwrap();
+    assert_eq!(count(&pool, r#"SELECT COUNT(*) FROM "GroupItems" WHERE "groupId" = 'g1'"#).await, 0);
+    assert_eq!(count(&pool, r#"SELECT COUNT(*) FROM "SyncTombstones" WHERE "id" = 'gi1'"#).await, 1);
+}
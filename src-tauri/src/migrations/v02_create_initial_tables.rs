@@ -0,0 +1,36 @@
+use tauri_plugin_sql::{Migration, MigrationKind};
+
+/// 版本2 - 原始的初始表创建（必须保留，不能修改，否则数据库会报错）
+pub fn migration() -> Migration {
+    Migration {
+        version: 2,
+        description: "create_initial_tables",
+        sql: r#"
+            CREATE TABLE  IF NOT EXISTS "CutItems" (
+                "id" UUID NOT NULL,
+                "content" TEXT NOT NULL,
+                "createTime" DATETIME NOT NULL,
+                PRIMARY KEY ("id")
+              );
+
+            CREATE TABLE IF NOT EXISTS "Groups" (
+            "id" UUID NOT NULL,
+            "name" VARCHAR(255) NOT NULL,
+            "createTime" DATETIME NOT NULL,
+            PRIMARY KEY ("id")
+            );
+
+            CREATE TABLE IF NOT EXISTS "GroupItems" (
+            "id" UUID NOT NULL,
+            "groupId" VARCHAR(255) NOT NULL,
+            "content" TEXT NOT NULL,
+            "title" VARCHAR(255),
+            "createTime" DATETIME NOT NULL,
+            "updateTime" DATETIME,
+            PRIMARY KEY ("id")
+            );
+
+            "#,
+        kind: MigrationKind::Up,
+    }
+}
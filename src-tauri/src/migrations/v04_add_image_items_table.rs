@@ -0,0 +1,21 @@
+use tauri_plugin_sql::{Migration, MigrationKind};
+
+/// 版本4 - 添加图片表（跳过版本3避免之前的冲突）
+pub fn migration() -> Migration {
+    Migration {
+        version: 4,
+        description: "add_image_items_table",
+        sql: r#"
+            CREATE TABLE IF NOT EXISTS "ImageItems" (
+            "id" UUID NOT NULL,
+            "content" TEXT NOT NULL,
+            "width" INTEGER,
+            "height" INTEGER,
+            "size" INTEGER,
+            "createTime" DATETIME NOT NULL,
+            PRIMARY KEY ("id")
+            );
+            "#,
+        kind: MigrationKind::Up,
+    }
+}
@@ -0,0 +1,13 @@
+use tauri_plugin_sql::{Migration, MigrationKind};
+
+/// 版本5 - 图片OCR识别文字
+pub fn migration() -> Migration {
+    Migration {
+        version: 5,
+        description: "add_image_ocr_text",
+        sql: r#"
+            ALTER TABLE "ImageItems" ADD COLUMN "ocrText" TEXT;
+            "#,
+        kind: MigrationKind::Up,
+    }
+}
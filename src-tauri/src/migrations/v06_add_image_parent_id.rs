@@ -0,0 +1,13 @@
+use tauri_plugin_sql::{Migration, MigrationKind};
+
+/// 版本6 - 编辑生成的图片关联原图
+pub fn migration() -> Migration {
+    Migration {
+        version: 6,
+        description: "add_image_parent_id",
+        sql: r#"
+            ALTER TABLE "ImageItems" ADD COLUMN "parentId" UUID;
+            "#,
+        kind: MigrationKind::Up,
+    }
+}
@@ -0,0 +1,14 @@
+use tauri_plugin_sql::{Migration, MigrationKind};
+
+/// 版本7 - 记录图片编码格式和编码后大小
+pub fn migration() -> Migration {
+    Migration {
+        version: 7,
+        description: "add_image_format_and_byte_size",
+        sql: r#"
+            ALTER TABLE "ImageItems" ADD COLUMN "format" VARCHAR(16);
+            ALTER TABLE "ImageItems" ADD COLUMN "byteSize" INTEGER;
+            "#,
+        kind: MigrationKind::Up,
+    }
+}
@@ -0,0 +1,13 @@
+use tauri_plugin_sql::{Migration, MigrationKind};
+
+/// 版本8 - 区分剪切板（clipboard）和鼠标选区（primary）记录
+pub fn migration() -> Migration {
+    Migration {
+        version: 8,
+        description: "add_cut_item_selection",
+        sql: r#"
+            ALTER TABLE "CutItems" ADD COLUMN "selection" VARCHAR(16) NOT NULL DEFAULT 'clipboard';
+            "#,
+        kind: MigrationKind::Up,
+    }
+}
@@ -0,0 +1,81 @@
+use tauri_plugin_sql::{Migration, MigrationKind};
+
+/// 版本9 - 多设备同步：修改时间、本机修改发件箱、删除记录和同步状态
+///
+/// 本机修改由触发器写入发件箱，合并其他设备的修改时设置 applying 标记跳过触发器
+pub fn migration() -> Migration {
+    Migration {
+        version: 9,
+        description: "add_sync_tables",
+        sql: r#"
+            ALTER TABLE "CutItems" ADD COLUMN "updateTime" DATETIME;
+
+            CREATE TABLE IF NOT EXISTS "SyncState" (
+            "key" VARCHAR(255) NOT NULL,
+            "value" TEXT NOT NULL,
+            PRIMARY KEY ("key")
+            );
+
+            CREATE TABLE IF NOT EXISTS "SyncOutbox" (
+            "seq" INTEGER PRIMARY KEY AUTOINCREMENT,
+            "entity" VARCHAR(16) NOT NULL,
+            "entityId" UUID NOT NULL,
+            "op" VARCHAR(16) NOT NULL,
+            "time" DATETIME NOT NULL
+            );
+
+            CREATE TABLE IF NOT EXISTS "SyncTombstones" (
+            "id" UUID NOT NULL,
+            "deleteTime" DATETIME NOT NULL,
+            PRIMARY KEY ("id")
+            );
+
+            CREATE TRIGGER IF NOT EXISTS "CutItems_sync_insert" AFTER INSERT ON "CutItems"
+            WHEN NOT EXISTS (SELECT 1 FROM "SyncState" WHERE "key" = 'applying')
+            BEGIN
+            INSERT INTO "SyncOutbox" ("entity", "entityId", "op", "time")
+            VALUES ('cut_item', NEW."id", 'insert', strftime('%Y-%m-%dT%H:%M:%fZ', 'now'));
+            END;
+
+            CREATE TRIGGER IF NOT EXISTS "CutItems_sync_update" AFTER UPDATE OF "content" ON "CutItems"
+            WHEN NOT EXISTS (SELECT 1 FROM "SyncState" WHERE "key" = 'applying')
+            BEGIN
+            INSERT INTO "SyncOutbox" ("entity", "entityId", "op", "time")
+            VALUES ('cut_item', NEW."id", 'update', strftime('%Y-%m-%dT%H:%M:%fZ', 'now'));
+            END;
+
+            CREATE TRIGGER IF NOT EXISTS "CutItems_sync_delete" AFTER DELETE ON "CutItems"
+            WHEN NOT EXISTS (SELECT 1 FROM "SyncState" WHERE "key" = 'applying')
+            BEGIN
+            INSERT INTO "SyncOutbox" ("entity", "entityId", "op", "time")
+            VALUES ('cut_item', OLD."id", 'delete', strftime('%Y-%m-%dT%H:%M:%fZ', 'now'));
+            INSERT OR REPLACE INTO "SyncTombstones" ("id", "deleteTime")
+            VALUES (OLD."id", strftime('%Y-%m-%dT%H:%M:%fZ', 'now'));
+            END;
+
+            CREATE TRIGGER IF NOT EXISTS "GroupItems_sync_insert" AFTER INSERT ON "GroupItems"
+            WHEN NOT EXISTS (SELECT 1 FROM "SyncState" WHERE "key" = 'applying')
+            BEGIN
+            INSERT INTO "SyncOutbox" ("entity", "entityId", "op", "time")
+            VALUES ('group_item', NEW."id", 'insert', strftime('%Y-%m-%dT%H:%M:%fZ', 'now'));
+            END;
+
+            CREATE TRIGGER IF NOT EXISTS "GroupItems_sync_update" AFTER UPDATE OF "groupId", "content", "title" ON "GroupItems"
+            WHEN NOT EXISTS (SELECT 1 FROM "SyncState" WHERE "key" = 'applying')
+            BEGIN
+            INSERT INTO "SyncOutbox" ("entity", "entityId", "op", "time")
+            VALUES ('group_item', NEW."id", 'update', strftime('%Y-%m-%dT%H:%M:%fZ', 'now'));
+            END;
+
+            CREATE TRIGGER IF NOT EXISTS "GroupItems_sync_delete" AFTER DELETE ON "GroupItems"
+            WHEN NOT EXISTS (SELECT 1 FROM "SyncState" WHERE "key" = 'applying')
+            BEGIN
+            INSERT INTO "SyncOutbox" ("entity", "entityId", "op", "time")
+            VALUES ('group_item', OLD."id", 'delete', strftime('%Y-%m-%dT%H:%M:%fZ', 'now'));
+            INSERT OR REPLACE INTO "SyncTombstones" ("id", "deleteTime")
+            VALUES (OLD."id", strftime('%Y-%m-%dT%H:%M:%fZ', 'now'));
+            END;
+            "#,
+        kind: MigrationKind::Up,
+    }
+}
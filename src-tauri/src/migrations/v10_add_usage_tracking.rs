@@ -0,0 +1,16 @@
+use tauri_plugin_sql::{Migration, MigrationKind};
+
+/// 版本10 - 使用次数和最近使用时间，用于按使用频率排序
+pub fn migration() -> Migration {
+    Migration {
+        version: 10,
+        description: "add_usage_tracking",
+        sql: r#"
+            ALTER TABLE "CutItems" ADD COLUMN "pasteCount" INTEGER NOT NULL DEFAULT 0;
+            ALTER TABLE "CutItems" ADD COLUMN "lastUsedAt" DATETIME;
+            ALTER TABLE "GroupItems" ADD COLUMN "pasteCount" INTEGER NOT NULL DEFAULT 0;
+            ALTER TABLE "GroupItems" ADD COLUMN "lastUsedAt" DATETIME;
+            "#,
+        kind: MigrationKind::Up,
+    }
+}
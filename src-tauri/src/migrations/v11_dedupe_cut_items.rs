@@ -0,0 +1,34 @@
+use tauri_plugin_sql::{Migration, MigrationKind};
+
+/// 版本11 - 内容哈希唯一索引，重复内容只保留一条记录
+///
+/// 合并已有的重复记录：保留最新的一条并累计次数，哈希由启动时的 db::backfill_content_hashes 补全
+pub fn migration() -> Migration {
+    Migration {
+        version: 11,
+        description: "dedupe_cut_items",
+        sql: r#"
+            ALTER TABLE "CutItems" ADD COLUMN "contentHash" VARCHAR(64);
+            ALTER TABLE "CutItems" ADD COLUMN "copyCount" INTEGER NOT NULL DEFAULT 1;
+
+            CREATE INDEX IF NOT EXISTS "CutItems_content_dedupe" ON "CutItems" ("content");
+
+            UPDATE "CutItems" SET
+            "copyCount" = (SELECT COUNT(*) FROM "CutItems" AS d WHERE d."content" = "CutItems"."content"),
+            "pasteCount" = (SELECT SUM(d."pasteCount") FROM "CutItems" AS d WHERE d."content" = "CutItems"."content"),
+            "lastUsedAt" = (SELECT MAX(d."lastUsedAt") FROM "CutItems" AS d WHERE d."content" = "CutItems"."content");
+
+            DELETE FROM "CutItems" WHERE EXISTS (
+            SELECT 1 FROM "CutItems" AS n
+            WHERE n."content" = "CutItems"."content"
+            AND (n."createTime" > "CutItems"."createTime"
+                OR (n."createTime" = "CutItems"."createTime" AND n."id" > "CutItems"."id"))
+            );
+
+            DROP INDEX IF EXISTS "CutItems_content_dedupe";
+
+            CREATE UNIQUE INDEX IF NOT EXISTS "CutItems_contentHash" ON "CutItems" ("contentHash");
+            "#,
+        kind: MigrationKind::Up,
+    }
+}
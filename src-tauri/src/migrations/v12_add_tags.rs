@@ -0,0 +1,44 @@
+use tauri_plugin_sql::{Migration, MigrationKind};
+
+/// 版本12 - 标签：文本历史、图片历史和分组内容都可以添加标签，记录删除时一并删除其标签
+pub fn migration() -> Migration {
+    Migration {
+        version: 12,
+        description: "add_tags",
+        sql: r#"
+            CREATE TABLE IF NOT EXISTS "Tags" (
+            "id" UUID NOT NULL,
+            "name" VARCHAR(255) NOT NULL COLLATE NOCASE,
+            "createTime" DATETIME NOT NULL,
+            PRIMARY KEY ("id"),
+            UNIQUE ("name")
+            );
+
+            CREATE TABLE IF NOT EXISTS "ItemTags" (
+            "tagId" UUID NOT NULL,
+            "itemId" UUID NOT NULL,
+            "itemType" VARCHAR(16) NOT NULL,
+            "createTime" DATETIME NOT NULL,
+            PRIMARY KEY ("tagId", "itemId")
+            );
+
+            CREATE INDEX IF NOT EXISTS "ItemTags_itemId" ON "ItemTags" ("itemId");
+
+            CREATE TRIGGER IF NOT EXISTS "CutItems_delete_tags" AFTER DELETE ON "CutItems"
+            BEGIN
+            DELETE FROM "ItemTags" WHERE "itemId" = OLD."id";
+            END;
+
+            CREATE TRIGGER IF NOT EXISTS "ImageItems_delete_tags" AFTER DELETE ON "ImageItems"
+            BEGIN
+            DELETE FROM "ItemTags" WHERE "itemId" = OLD."id";
+            END;
+
+            CREATE TRIGGER IF NOT EXISTS "GroupItems_delete_tags" AFTER DELETE ON "GroupItems"
+            BEGIN
+            DELETE FROM "ItemTags" WHERE "itemId" = OLD."id";
+            END;
+            "#,
+        kind: MigrationKind::Up,
+    }
+}
@@ -0,0 +1,14 @@
+use tauri_plugin_sql::{Migration, MigrationKind};
+
+/// 版本13 - 回收站：删除记录时只设置删除时间，超过保留天数后永久删除
+pub fn migration() -> Migration {
+    Migration {
+        version: 13,
+        description: "add_soft_delete",
+        sql: r#"
+            ALTER TABLE "CutItems" ADD COLUMN "deletedAt" DATETIME;
+            ALTER TABLE "ImageItems" ADD COLUMN "deletedAt" DATETIME;
+            "#,
+        kind: MigrationKind::Up,
+    }
+}
@@ -0,0 +1,32 @@
+use tauri_plugin_sql::{Migration, MigrationKind};
+
+/// 版本14 - 修改文本历史和分组内容时保存修改前的版本
+pub fn migration() -> Migration {
+    Migration {
+        version: 14,
+        description: "add_item_revisions",
+        sql: r#"
+            CREATE TABLE IF NOT EXISTS "ItemRevisions" (
+            "id" UUID NOT NULL,
+            "itemId" UUID NOT NULL,
+            "itemType" VARCHAR(16) NOT NULL,
+            "content" TEXT NOT NULL,
+            "createTime" DATETIME NOT NULL,
+            PRIMARY KEY ("id")
+            );
+
+            CREATE INDEX IF NOT EXISTS "ItemRevisions_itemId" ON "ItemRevisions" ("itemId", "createTime");
+
+            CREATE TRIGGER IF NOT EXISTS "CutItems_delete_revisions" AFTER DELETE ON "CutItems"
+            BEGIN
+            DELETE FROM "ItemRevisions" WHERE "itemId" = OLD."id";
+            END;
+
+            CREATE TRIGGER IF NOT EXISTS "GroupItems_delete_revisions" AFTER DELETE ON "GroupItems"
+            BEGIN
+            DELETE FROM "ItemRevisions" WHERE "itemId" = OLD."id";
+            END;
+            "#,
+        kind: MigrationKind::Up,
+    }
+}
@@ -0,0 +1,29 @@
+use tauri_plugin_sql::{Migration, MigrationKind};
+
+/// 版本15 - 大文本压缩存储：content 只保存预览，完整内容 zstd 压缩后保存在 contentBlob
+///
+/// 已有的大文本由 db::compress_large_items 在后台压缩，内容哈希不变时不再写入同步发件箱
+pub fn migration() -> Migration {
+    Migration {
+        version: 15,
+        description: "add_compressed_content",
+        sql: r#"
+            ALTER TABLE "CutItems" ADD COLUMN "contentBlob" BLOB;
+            ALTER TABLE "CutItems" ADD COLUMN "preview" TEXT;
+            ALTER TABLE "CutItems" ADD COLUMN "length" INTEGER;
+
+            UPDATE "CutItems" SET "preview" = substr("content", 1, 200), "length" = length("content");
+
+            DROP TRIGGER IF EXISTS "CutItems_sync_update";
+
+            CREATE TRIGGER IF NOT EXISTS "CutItems_sync_update" AFTER UPDATE OF "content" ON "CutItems"
+            WHEN NOT EXISTS (SELECT 1 FROM "SyncState" WHERE "key" = 'applying')
+            AND OLD."contentHash" IS NOT NEW."contentHash"
+            BEGIN
+            INSERT INTO "SyncOutbox" ("entity", "entityId", "op", "time")
+            VALUES ('cut_item', NEW."id", 'update', strftime('%Y-%m-%dT%H:%M:%fZ', 'now'));
+            END;
+            "#,
+        kind: MigrationKind::Up,
+    }
+}
@@ -0,0 +1,17 @@
+use tauri_plugin_sql::{Migration, MigrationKind};
+
+/// 版本16 - 应用状态键值表（如上次整理数据库的时间），不参与同步
+pub fn migration() -> Migration {
+    Migration {
+        version: 16,
+        description: "add_app_state",
+        sql: r#"
+            CREATE TABLE IF NOT EXISTS "AppState" (
+            "key" VARCHAR(255) NOT NULL,
+            "value" TEXT NOT NULL,
+            PRIMARY KEY ("key")
+            );
+            "#,
+        kind: MigrationKind::Up,
+    }
+}
@@ -0,0 +1,74 @@
+use tauri_plugin_sql::{Migration, MigrationKind};
+
+/// 版本17 - 按创建时间排序和按分组查询的索引，GroupItems.groupId 外键（删除分组时一并删除分组内容）
+///
+/// SQLite 不能给已有的表添加外键，需要重建 GroupItems 表及其触发器；
+/// 所属分组已不存在的分组内容归入新建的「恢复的分组」，不会丢失
+pub fn migration() -> Migration {
+    Migration {
+        version: 17,
+        description: "add_indexes_and_group_fk",
+        sql: r#"
+            INSERT INTO "Groups" ("id", "name", "createTime")
+            SELECT "groupId", '恢复的分组', MIN("createTime") FROM "GroupItems"
+            WHERE "groupId" NOT IN (SELECT "id" FROM "Groups")
+            GROUP BY "groupId";
+
+            CREATE TABLE "GroupItems_new" (
+            "id" TEXT NOT NULL,
+            "groupId" TEXT NOT NULL REFERENCES "Groups" ("id") ON DELETE CASCADE,
+            "content" TEXT NOT NULL,
+            "title" VARCHAR(255),
+            "createTime" TEXT NOT NULL,
+            "updateTime" TEXT,
+            "pasteCount" INTEGER NOT NULL DEFAULT 0,
+            "lastUsedAt" TEXT,
+            PRIMARY KEY ("id")
+            );
+
+            INSERT INTO "GroupItems_new" ("id", "groupId", "content", "title", "createTime", "updateTime", "pasteCount", "lastUsedAt")
+            SELECT "id", "groupId", "content", "title", "createTime", "updateTime", "pasteCount", "lastUsedAt" FROM "GroupItems";
+
+            DROP TABLE "GroupItems";
+            ALTER TABLE "GroupItems_new" RENAME TO "GroupItems";
+
+            CREATE INDEX IF NOT EXISTS "GroupItems_groupId" ON "GroupItems" ("groupId", "createTime");
+            CREATE INDEX IF NOT EXISTS "CutItems_createTime" ON "CutItems" ("createTime");
+            CREATE INDEX IF NOT EXISTS "ImageItems_createTime" ON "ImageItems" ("createTime");
+
+            CREATE TRIGGER IF NOT EXISTS "GroupItems_sync_insert" AFTER INSERT ON "GroupItems"
+            WHEN NOT EXISTS (SELECT 1 FROM "SyncState" WHERE "key" = 'applying')
+            BEGIN
+            INSERT INTO "SyncOutbox" ("entity", "entityId", "op", "time")
+            VALUES ('group_item', NEW."id", 'insert', strftime('%Y-%m-%dT%H:%M:%fZ', 'now'));
+            END;
+
+            CREATE TRIGGER IF NOT EXISTS "GroupItems_sync_update" AFTER UPDATE OF "groupId", "content", "title" ON "GroupItems"
+            WHEN NOT EXISTS (SELECT 1 FROM "SyncState" WHERE "key" = 'applying')
+            BEGIN
+            INSERT INTO "SyncOutbox" ("entity", "entityId", "op", "time")
+            VALUES ('group_item', NEW."id", 'update', strftime('%Y-%m-%dT%H:%M:%fZ', 'now'));
+            END;
+
+            CREATE TRIGGER IF NOT EXISTS "GroupItems_sync_delete" AFTER DELETE ON "GroupItems"
+            WHEN NOT EXISTS (SELECT 1 FROM "SyncState" WHERE "key" = 'applying')
+            BEGIN
+            INSERT INTO "SyncOutbox" ("entity", "entityId", "op", "time")
+            VALUES ('group_item', OLD."id", 'delete', strftime('%Y-%m-%dT%H:%M:%fZ', 'now'));
+            INSERT OR REPLACE INTO "SyncTombstones" ("id", "deleteTime")
+            VALUES (OLD."id", strftime('%Y-%m-%dT%H:%M:%fZ', 'now'));
+            END;
+
+            CREATE TRIGGER IF NOT EXISTS "GroupItems_delete_tags" AFTER DELETE ON "GroupItems"
+            BEGIN
+            DELETE FROM "ItemTags" WHERE "itemId" = OLD."id";
+            END;
+
+            CREATE TRIGGER IF NOT EXISTS "GroupItems_delete_revisions" AFTER DELETE ON "GroupItems"
+            BEGIN
+            DELETE FROM "ItemRevisions" WHERE "itemId" = OLD."id";
+            END;
+            "#,
+        kind: MigrationKind::Up,
+    }
+}
@@ -0,0 +1,32 @@
+use tauri_plugin_sql::{Migration, MigrationKind};
+
+/// 版本18 - 同步分组的重命名和删除：分组修改时间和 Groups 的发件箱触发器
+///
+/// 重命名时由触发器更新 updateTime，合并其他设备的修改时以此判断新旧
+pub fn migration() -> Migration {
+    Migration {
+        version: 18,
+        description: "add_group_sync",
+        sql: r#"
+            ALTER TABLE "Groups" ADD COLUMN "updateTime" TEXT;
+
+            CREATE TRIGGER IF NOT EXISTS "Groups_sync_update" AFTER UPDATE OF "name" ON "Groups"
+            WHEN NOT EXISTS (SELECT 1 FROM "SyncState" WHERE "key" = 'applying')
+            BEGIN
+            UPDATE "Groups" SET "updateTime" = strftime('%Y-%m-%dT%H:%M:%fZ', 'now') WHERE "id" = NEW."id";
+            INSERT INTO "SyncOutbox" ("entity", "entityId", "op", "time")
+            VALUES ('group', NEW."id", 'update', strftime('%Y-%m-%dT%H:%M:%fZ', 'now'));
+            END;
+
+            CREATE TRIGGER IF NOT EXISTS "Groups_sync_delete" AFTER DELETE ON "Groups"
+            WHEN NOT EXISTS (SELECT 1 FROM "SyncState" WHERE "key" = 'applying')
+            BEGIN
+            INSERT INTO "SyncOutbox" ("entity", "entityId", "op", "time")
+            VALUES ('group', OLD."id", 'delete', strftime('%Y-%m-%dT%H:%M:%fZ', 'now'));
+            INSERT OR REPLACE INTO "SyncTombstones" ("id", "deleteTime")
+            VALUES (OLD."id", strftime('%Y-%m-%dT%H:%M:%fZ', 'now'));
+            END;
+            "#,
+        kind: MigrationKind::Up,
+    }
+}
@@ -0,0 +1,23 @@
+use tauri_plugin_sql::{Migration, MigrationKind};
+
+/// 版本19 - 同步文本记录移入和移出回收站
+///
+/// 移入或移出回收站时由触发器更新 updateTime，与其他设备的修改按时间先后合并
+pub fn migration() -> Migration {
+    Migration {
+        version: 19,
+        description: "add_trash_sync",
+        sql: r#"
+            CREATE TRIGGER IF NOT EXISTS "CutItems_sync_trash" AFTER UPDATE OF "deletedAt" ON "CutItems"
+            WHEN NOT EXISTS (SELECT 1 FROM "SyncState" WHERE "key" = 'applying')
+            AND OLD."deletedAt" IS NOT NEW."deletedAt"
+            BEGIN
+            UPDATE "CutItems" SET "updateTime" = strftime('%Y-%m-%dT%H:%M:%fZ', 'now') WHERE "id" = NEW."id";
+            INSERT INTO "SyncOutbox" ("entity", "entityId", "op", "time")
+            VALUES ('cut_item', NEW."id", CASE WHEN NEW."deletedAt" IS NULL THEN 'restore' ELSE 'trash' END,
+            strftime('%Y-%m-%dT%H:%M:%fZ', 'now'));
+            END;
+            "#,
+        kind: MigrationKind::Up,
+    }
+}
@@ -0,0 +1,17 @@
+use tauri_plugin_sql::{Migration, MigrationKind};
+
+/// 版本20 - 剪切板（clipboard）和鼠标选区（primary）的相同内容分别记录
+///
+/// 内容哈希的唯一索引改为按选区区分，两种来源的记录不再合并为一条
+pub fn migration() -> Migration {
+    Migration {
+        version: 20,
+        description: "separate_selection_duplicates",
+        sql: r#"
+            DROP INDEX IF EXISTS "CutItems_contentHash";
+
+            CREATE UNIQUE INDEX IF NOT EXISTS "CutItems_selection_contentHash" ON "CutItems" ("selection", "contentHash");
+            "#,
+        kind: MigrationKind::Up,
+    }
+}
@@ -0,0 +1,13 @@
+use tauri_plugin_sql::{Migration, MigrationKind};
+
+/// 版本21 - 历史版本和文本历史一样压缩存储大文本：content 为预览，contentBlob 为 zstd 压缩的完整内容
+pub fn migration() -> Migration {
+    Migration {
+        version: 21,
+        description: "compress_revisions",
+        sql: r#"
+            ALTER TABLE "ItemRevisions" ADD COLUMN "contentBlob" BLOB;
+            "#,
+        kind: MigrationKind::Up,
+    }
+}
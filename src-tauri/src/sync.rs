@@ -0,0 +1,965 @@
+//! 基于文件夹的多设备同步
+//!
+//! 每台设备把本机的修改追加写入同步目录下的 `<设备ID>.jsonl`（只追加、不修改），
+//! 并读取其他设备的日志合并到本地数据库，同一条记录的冲突以 updateTime 较新的修改为准。
+//! 同步目录可以是 Syncthing、Nextcloud 等工具同步的任意文件夹。
+//!
+//! 本机修改由数据库触发器写入 SyncOutbox，因此前端和后端的写入都会被记录；
+//! 只同步文本历史、分组和分组内容，图片不参与同步。
+
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use sqlx::sqlite::{SqliteConnection, SqlitePool};
+use std::ffi::OsStr;
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, Seek, SeekFrom, Write};
+use std::path::Path;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+
+use crate::config::AppConfig;
+use crate::db;
+use crate::error::{AppError, AppResult};
+
+/// 合并了其他设备的修改后通知前端刷新的事件，载荷为 SyncReport
+pub const SYNC_COMPLETED_EVENT: &str = "sync-completed";
+
+/// 修改日志文件扩展名
+const LOG_EXTENSION: &str = "jsonl";
+
+/// SyncState 中的键
+const STATE_DEVICE_ID: &str = "deviceId";
+const STATE_APPLYING: &str = "applying";
+const STATE_EXPORTED_FOLDER: &str = "exportedFolder";
+
+/// 修改中没有分组名称（早期版本导出的所属分组已不存在的内容）时创建的分组名称，与版本17 迁移一致
+const RECOVERED_GROUP_NAME: &str = "恢复的分组";
+
+// 同一时间只允许一个同步任务
+lazy_static! {
+    static ref SYNC_LOCK: tokio::sync::Mutex<()> = tokio::sync::Mutex::new(());
+}
+
+fn default_selection() -> String {
+    db::SELECTION_CLIPBOARD.to_string()
+}
+
+/// 一条修改
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum Change {
+    /// 新增文本记录
+    ItemAdded {
+        id: String,
+        content: String,
+        #[serde(default = "default_selection")]
+        selection: String,
+        create_time: String,
+        update_time: String,
+    },
+    /// 修改文本记录内容
+    ItemEdited {
+        id: String,
+        content: String,
+        update_time: String,
+    },
+    /// 删除文本记录
+    ItemDeleted { id: String, update_time: String },
+    /// 文本记录移入回收站
+    ItemTrashed { id: String, update_time: String },
+    /// 文本记录从回收站恢复
+    ItemRestored { id: String, update_time: String },
+    /// 新增或修改分组内容，分组不存在时按 group_name 创建
+    Grouped {
+        id: String,
+        group_id: String,
+        #[serde(default)]
+        group_name: Option<String>,
+        title: Option<String>,
+        content: String,
+        create_time: String,
+        update_time: String,
+    },
+    /// 删除分组内容
+    Ungrouped { id: String, update_time: String },
+    /// 重命名分组，分组不存在时创建
+    GroupRenamed {
+        id: String,
+        name: String,
+        update_time: String,
+    },
+    /// 删除分组及其中的内容
+    GroupDeleted { id: String, update_time: String },
+}
+
+/// 修改日志中的一行
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChangeEntry {
+    /// 产生修改的设备ID
+    pub device: String,
+    pub change: Change,
+}
+
+/// 一次同步的结果
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct SyncReport {
+    /// 写入本机日志的修改数
+    pub exported: usize,
+    /// 合并到本地数据库的修改数
+    pub imported: usize,
+}
+
+#[derive(sqlx::FromRow)]
+#[sqlx(rename_all = "camelCase")]
+struct OutboxRow {
+    seq: i64,
+    entity: String,
+    entity_id: String,
+    op: String,
+    time: String,
+}
+
+async fn get_state<'e>(executor: impl sqlx::SqliteExecutor<'e>, key: &str) -> AppResult<Option<String>> {
+    Ok(sqlx::query_scalar("SELECT value FROM SyncState WHERE key = ?")
+        .bind(key)
+        .fetch_optional(executor)
+        .await?)
+}
+
+async fn set_state<'e>(executor: impl sqlx::SqliteExecutor<'e>, key: &str, value: &str) -> AppResult<()> {
+    sqlx::query("INSERT OR REPLACE INTO SyncState (key, value) VALUES (?, ?)")
+        .bind(key)
+        .bind(value)
+        .execute(executor)
+        .await?;
+    Ok(())
+}
+
+async fn delete_state<'e>(executor: impl sqlx::SqliteExecutor<'e>, key: &str) -> AppResult<()> {
+    sqlx::query("DELETE FROM SyncState WHERE key = ?")
+        .bind(key)
+        .execute(executor)
+        .await?;
+    Ok(())
+}
+
+/// 暂停记录本机修改：设置 applying 标记，之后的修改不会被触发器写入发件箱和删除记录，
+/// 需要在同一事务中调用 resume_outbox
+pub(crate) async fn suspend_outbox(conn: &mut SqliteConnection) -> AppResult<()> {
+    set_state(conn, STATE_APPLYING, "1").await
+}
+
+/// 恢复记录本机修改
+pub(crate) async fn resume_outbox(conn: &mut SqliteConnection) -> AppResult<()> {
+    delete_state(conn, STATE_APPLYING).await
+}
+
+/// 本机设备ID，首次使用时生成
+pub async fn device_id(pool: &SqlitePool) -> AppResult<String> {
+    if let Some(id) = get_state(pool, STATE_DEVICE_ID).await? {
+        return Ok(id);
+    }
+
+    let id = uuid::Uuid::new_v4().to_string();
+    set_state(pool, STATE_DEVICE_ID, &id).await?;
+    Ok(id)
+}
+
+/// 内部函数：把发件箱中的一条记录转换为修改，记录已被删除时返回 None
+async fn change_for(pool: &SqlitePool, row: &OutboxRow) -> AppResult<Option<Change>> {
+    let change = match (row.entity.as_str(), row.op.as_str()) {
+        ("cut_item", "delete") => Some(Change::ItemDeleted {
+            id: row.entity_id.clone(),
+            update_time: row.time.clone(),
+        }),
+        ("cut_item", "trash") => Some(Change::ItemTrashed {
+            id: row.entity_id.clone(),
+            update_time: row.time.clone(),
+        }),
+        ("cut_item", "restore") => Some(Change::ItemRestored {
+            id: row.entity_id.clone(),
+            update_time: row.time.clone(),
+        }),
+        ("cut_item", op) => sqlx::query_as::<_, (String, Option<Vec<u8>>, String, String, Option<String>)>(
+            "SELECT content, contentBlob, selection, createTime, updateTime FROM CutItems WHERE id = ?",
+        )
+        .bind(&row.entity_id)
+        .fetch_optional(pool)
+        .await?
+        .map(|(content, blob, selection, create_time, update_time)| {
+            db::full_content(content, blob).map(|content| (content, selection, create_time, update_time))
+        })
+        .transpose()?
+        .map(|(content, selection, create_time, update_time)| match op {
+            "insert" => Change::ItemAdded {
+                id: row.entity_id.clone(),
+                content,
+                selection,
+                update_time: update_time.unwrap_or_else(|| create_time.clone()),
+                create_time,
+            },
+            _ => Change::ItemEdited {
+                id: row.entity_id.clone(),
+                content,
+                update_time: update_time.unwrap_or_else(|| row.time.clone()),
+            },
+        }),
+        ("group_item", "delete") => Some(Change::Ungrouped {
+            id: row.entity_id.clone(),
+            update_time: row.time.clone(),
+        }),
+        ("group_item", op) => {
+            let group_item = sqlx::query_as::<_, (String, Option<String>, Option<String>, String, String, Option<String>)>(
+                "SELECT gi.groupId, g.name, gi.title, gi.content, gi.createTime, gi.updateTime
+                 FROM GroupItems gi LEFT JOIN Groups g ON g.id = gi.groupId
+                 WHERE gi.id = ?",
+            )
+            .bind(&row.entity_id)
+            .fetch_optional(pool)
+            .await?;
+
+            group_item.map(|(group_id, group_name, title, content, create_time, update_time)| {
+                let fallback = if op == "insert" { create_time.clone() } else { row.time.clone() };
+                Change::Grouped {
+                    id: row.entity_id.clone(),
+                    group_id,
+                    group_name,
+                    title,
+                    content,
+                    create_time,
+                    update_time: update_time.unwrap_or(fallback),
+                }
+            })
+        }
+        ("group", "delete") => Some(Change::GroupDeleted {
+            id: row.entity_id.clone(),
+            update_time: row.time.clone(),
+        }),
+        ("group", _) => sqlx::query_as::<_, (String, Option<String>)>("SELECT name, updateTime FROM Groups WHERE id = ?")
+            .bind(&row.entity_id)
+            .fetch_optional(pool)
+            .await?
+            .map(|(name, update_time)| Change::GroupRenamed {
+                id: row.entity_id.clone(),
+                name,
+                update_time: update_time.unwrap_or_else(|| row.time.clone()),
+            }),
+        _ => None,
+    };
+
+    Ok(change)
+}
+
+/// 内部函数：当前全部文本历史和分组内容，首次同步到某个目录时作为初始快照
+async fn snapshot(pool: &SqlitePool) -> AppResult<Vec<Change>> {
+    let items = sqlx::query_as::<_, (String, String, Option<Vec<u8>>, String, String, Option<String>)>(
+        "SELECT id, content, contentBlob, selection, createTime, updateTime FROM CutItems
+         WHERE deletedAt IS NULL ORDER BY createTime ASC",
+    )
+    .fetch_all(pool)
+    .await?
+    .into_iter()
+    .map(|(id, content, blob, selection, create_time, update_time)| {
+        Ok((id, db::full_content(content, blob)?, selection, create_time, update_time))
+    })
+    .collect::<AppResult<Vec<_>>>()?;
+
+    let group_items = sqlx::query_as::<_, (String, String, Option<String>, Option<String>, String, String, Option<String>)>(
+        "SELECT gi.id, gi.groupId, g.name, gi.title, gi.content, gi.createTime, gi.updateTime
+         FROM GroupItems gi LEFT JOIN Groups g ON g.id = gi.groupId
+         ORDER BY gi.createTime ASC",
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let items = items.into_iter().map(|(id, content, selection, create_time, update_time)| Change::ItemAdded {
+        id,
+        content,
+        selection,
+        update_time: update_time.unwrap_or_else(|| create_time.clone()),
+        create_time,
+    });
+    let group_items = group_items.into_iter().map(
+        |(id, group_id, group_name, title, content, create_time, update_time)| Change::Grouped {
+            id,
+            group_id,
+            group_name,
+            title,
+            content,
+            update_time: update_time.unwrap_or_else(|| create_time.clone()),
+            create_time,
+        },
+    );
+
+    Ok(items.chain(group_items).collect())
+}
+
+/// 内部函数：把修改追加写入日志文件，整批一次写入避免产生不完整的行
+fn append_changes(path: &Path, device: &str, changes: Vec<Change>) -> AppResult<()> {
+    let mut content = String::new();
+    for change in changes {
+        let entry = ChangeEntry { device: device.to_string(), change };
+        let line = serde_json::to_string(&entry)
+            .map_err(|e| AppError::Internal(format!("Failed to serialize change: {}", e)))?;
+        content.push_str(&line);
+        content.push('\n');
+    }
+
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(|e| AppError::Io(format!("Failed to open change log {}: {}", path.display(), e)))?;
+    file.write_all(content.as_bytes())
+        .and_then(|_| file.sync_all())
+        .map_err(|e| AppError::Io(format!("Failed to write change log {}: {}", path.display(), e)))
+}
+
+/// 把本机的修改写入同步目录下的本机日志，返回写入的修改数
+pub async fn export_changes(pool: &SqlitePool, dir: &Path, device: &str) -> AppResult<usize> {
+    let folder = dir.to_string_lossy();
+    let rows = sqlx::query_as::<_, OutboxRow>("SELECT * FROM SyncOutbox ORDER BY seq ASC")
+        .fetch_all(pool)
+        .await?;
+    let max_seq = rows.last().map(|row| row.seq);
+
+    // 首次同步到该目录时导出全部记录，之前的发件箱记录已包含在快照中
+    let changes = if get_state(pool, STATE_EXPORTED_FOLDER).await?.as_deref() != Some(&*folder) {
+        snapshot(pool).await?
+    } else {
+        let mut changes = Vec::with_capacity(rows.len());
+        for row in &rows {
+            if let Some(change) = change_for(pool, row).await? {
+                changes.push(change);
+            }
+        }
+        changes
+    };
+
+    let exported = changes.len();
+    if exported > 0 {
+        append_changes(&dir.join(format!("{}.{}", device, LOG_EXTENSION)), device, changes)?;
+    }
+
+    // 只删除已读取的记录，导出期间新产生的修改留到下次同步
+    if let Some(max_seq) = max_seq {
+        sqlx::query("DELETE FROM SyncOutbox WHERE seq <= ?")
+            .bind(max_seq)
+            .execute(pool)
+            .await?;
+    }
+    set_state(pool, STATE_EXPORTED_FOLDER, &folder).await?;
+
+    Ok(exported)
+}
+
+/// 内部函数：从指定偏移量读取日志中的完整行，返回修改和新的偏移量
+///
+/// 最后一行不完整（其他设备仍在写入或文件尚未同步完）时留到下次读取
+fn read_log(path: &Path, offset: u64) -> AppResult<(Vec<ChangeEntry>, u64)> {
+    let io_error = |e: std::io::Error| AppError::Io(format!("Failed to read change log {}: {}", path.display(), e));
+
+    let mut file = File::open(path).map_err(io_error)?;
+    // 日志被替换为更短的文件时从头读取，合并是幂等的
+    let offset = if file.metadata().map_err(io_error)?.len() < offset { 0 } else { offset };
+    file.seek(SeekFrom::Start(offset)).map_err(io_error)?;
+
+    let mut reader = BufReader::new(file);
+    let mut entries = Vec::new();
+    let mut consumed = offset;
+    let mut line = String::new();
+    loop {
+        line.clear();
+        let read = reader.read_line(&mut line).map_err(io_error)?;
+        if read == 0 || !line.ends_with('\n') {
+            break;
+        }
+        consumed += read as u64;
+
+        match serde_json::from_str::<ChangeEntry>(line.trim()) {
+            Ok(entry) => entries.push(entry),
+            Err(e) => tracing::warn!("Skipping invalid change in {}: {}", path.display(), e),
+        }
+    }
+
+    Ok((entries, consumed))
+}
+
+/// 内部函数：记录在 time 或之后被删除时返回 true
+async fn is_deleted_since(conn: &mut SqliteConnection, id: &str, time: &str) -> AppResult<bool> {
+    let deleted: Option<String> = sqlx::query_scalar("SELECT deleteTime FROM SyncTombstones WHERE id = ?")
+        .bind(id)
+        .fetch_optional(&mut *conn)
+        .await?;
+    Ok(deleted.is_some_and(|deleted| deleted.as_str() >= time))
+}
+
+/// 内部函数：本地记录（或删除记录）的时间早于 time 时返回 true
+async fn is_newer(conn: &mut SqliteConnection, table: &str, id: &str, time: &str) -> AppResult<bool> {
+    if is_deleted_since(conn, id, time).await? {
+        return Ok(false);
+    }
+
+    let local: Option<String> = sqlx::query_scalar(&format!(
+        "SELECT COALESCE(updateTime, createTime) FROM {} WHERE id = ?",
+        table
+    ))
+    .bind(id)
+    .fetch_optional(&mut *conn)
+    .await?;
+    Ok(local.map_or(true, |local| local.as_str() < time))
+}
+
+/// 内部函数：删除本地记录并记录删除时间，本地修改更新时保留记录
+async fn delete_entity(conn: &mut SqliteConnection, table: &str, id: &str, time: &str) -> AppResult<bool> {
+    sqlx::query(
+        "INSERT INTO SyncTombstones (id, deleteTime) VALUES (?, ?)
+         ON CONFLICT(id) DO UPDATE SET deleteTime = MAX(deleteTime, excluded.deleteTime)",
+    )
+    .bind(id)
+    .bind(time)
+    .execute(&mut *conn)
+    .await?;
+
+    let result = sqlx::query(&format!(
+        "DELETE FROM {} WHERE id = ? AND COALESCE(updateTime, createTime) <= ?",
+        table
+    ))
+    .bind(id)
+    .bind(time)
+    .execute(&mut *conn)
+    .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+/// 内部函数：处理与同一选区其他文本记录内容相同的修改，返回是否继续合并
+///
+/// 同一选区内容哈希唯一，各设备各自记录的相同内容在合并时只保留 ID 较小的一条，保证各设备结果一致
+async fn resolve_duplicate(conn: &mut SqliteConnection, id: &str, selection: &str, hash: &str) -> AppResult<bool> {
+    let other: Option<String> =
+        sqlx::query_scalar("SELECT id FROM CutItems WHERE selection = ? AND contentHash = ? AND id != ?")
+            .bind(selection)
+            .bind(hash)
+            .bind(id)
+            .fetch_optional(&mut *conn)
+            .await?;
+
+    match other {
+        None => Ok(true),
+        Some(other) if id < other.as_str() => {
+            sqlx::query("DELETE FROM CutItems WHERE id = ?")
+                .bind(&other)
+                .execute(&mut *conn)
+                .await?;
+            Ok(true)
+        }
+        Some(_) => Ok(false),
+    }
+}
+
+/// 内部函数：合并一条修改，返回本地数据是否发生变化
+///
+/// 文本超过 max_text_bytes 时跳过该修改（与本机记录文本时的限制相同）
+async fn apply_change(conn: &mut SqliteConnection, change: &Change, max_text_bytes: usize) -> AppResult<bool> {
+    let content = match change {
+        Change::ItemAdded { content, .. } | Change::ItemEdited { content, .. } | Change::Grouped { content, .. } => {
+            Some(content)
+        }
+        _ => None,
+    };
+    if let Some(content) = content.filter(|content| content.len() > max_text_bytes) {
+        tracing::warn!("Skipping synced text of {} bytes, max size is {} bytes", content.len(), max_text_bytes);
+        return Ok(false);
+    }
+
+    match change {
+        Change::ItemAdded { id, content, selection, create_time, update_time } => {
+            let hash = db::content_hash(content);
+            if !is_newer(conn, "CutItems", id, update_time).await?
+                || !resolve_duplicate(conn, id, selection, &hash).await?
+            {
+                return Ok(false);
+            }
+            sqlx::query(
+                "INSERT INTO CutItems (id, content, selection, createTime, updateTime, contentHash, preview, length)
+                 VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+                 ON CONFLICT(id) DO UPDATE SET content = excluded.content, updateTime = excluded.updateTime,
+                 contentHash = excluded.contentHash, contentBlob = NULL, preview = excluded.preview, length = excluded.length",
+            )
+            .bind(id)
+            .bind(content)
+            .bind(selection)
+            .bind(create_time)
+            .bind(update_time)
+            .bind(&hash)
+            .bind(db::preview_of(content))
+            .bind(content.chars().count() as i64)
+            .execute(&mut *conn)
+            .await?;
+        }
+        Change::ItemEdited { id, content, update_time } => {
+            let hash = db::content_hash(content);
+            // 本地没有该记录时按新增记录的默认选区处理
+            let selection: String = sqlx::query_scalar("SELECT selection FROM CutItems WHERE id = ?")
+                .bind(id)
+                .fetch_optional(&mut *conn)
+                .await?
+                .unwrap_or_else(default_selection);
+            if !is_newer(conn, "CutItems", id, update_time).await?
+                || !resolve_duplicate(conn, id, &selection, &hash).await?
+            {
+                return Ok(false);
+            }
+            // 本地没有该记录时（新增记录的修改先到达）以修改时间作为创建时间
+            sqlx::query(
+                "INSERT INTO CutItems (id, content, createTime, updateTime, contentHash, preview, length)
+                 VALUES (?, ?, ?, ?, ?, ?, ?)
+                 ON CONFLICT(id) DO UPDATE SET content = excluded.content, updateTime = excluded.updateTime,
+                 contentHash = excluded.contentHash, contentBlob = NULL, preview = excluded.preview, length = excluded.length",
+            )
+            .bind(id)
+            .bind(content)
+            .bind(update_time)
+            .bind(update_time)
+            .bind(&hash)
+            .bind(db::preview_of(content))
+            .bind(content.chars().count() as i64)
+            .execute(&mut *conn)
+            .await?;
+        }
+        Change::ItemDeleted { id, update_time } => {
+            return delete_entity(conn, "CutItems", id, update_time).await;
+        }
+        Change::ItemTrashed { id, update_time } | Change::ItemRestored { id, update_time } => {
+            if !is_newer(conn, "CutItems", id, update_time).await? {
+                return Ok(false);
+            }
+            let deleted_at = matches!(change, Change::ItemTrashed { .. }).then_some(update_time.as_str());
+            let result = sqlx::query("UPDATE CutItems SET deletedAt = ?, updateTime = ? WHERE id = ?")
+                .bind(deleted_at)
+                .bind(update_time)
+                .bind(id)
+                .execute(&mut *conn)
+                .await?;
+            return Ok(result.rows_affected() > 0);
+        }
+        Change::Grouped { id, group_id, group_name, title, content, create_time, update_time } => {
+            // 所属分组在之后被删除时不再恢复
+            if !is_newer(conn, "GroupItems", id, update_time).await? || is_deleted_since(conn, group_id, update_time).await? {
+                return Ok(false);
+            }
+            // 没有名称时 INSERT OR IGNORE 会因 name 不能为空而跳过，之后的分组内容违反外键
+            let group_name = group_name.as_deref().filter(|name| !name.is_empty()).unwrap_or(RECOVERED_GROUP_NAME);
+            sqlx::query("INSERT OR IGNORE INTO Groups (id, name, createTime) VALUES (?, ?, ?)")
+                .bind(group_id)
+                .bind(group_name)
+                .bind(create_time)
+                .execute(&mut *conn)
+                .await?;
+            sqlx::query(
+                "INSERT INTO GroupItems (id, groupId, content, title, createTime, updateTime) VALUES (?, ?, ?, ?, ?, ?)
+                 ON CONFLICT(id) DO UPDATE SET groupId = excluded.groupId, content = excluded.content,
+                 title = excluded.title, updateTime = excluded.updateTime",
+            )
+            .bind(id)
+            .bind(group_id)
+            .bind(content)
+            .bind(title)
+            .bind(create_time)
+            .bind(update_time)
+            .execute(&mut *conn)
+            .await?;
+        }
+        Change::Ungrouped { id, update_time } => {
+            return delete_entity(conn, "GroupItems", id, update_time).await;
+        }
+        Change::GroupRenamed { id, name, update_time } => {
+            if !is_newer(conn, "Groups", id, update_time).await? {
+                return Ok(false);
+            }
+            sqlx::query(
+                "INSERT INTO Groups (id, name, createTime, updateTime) VALUES (?, ?, ?, ?)
+                 ON CONFLICT(id) DO UPDATE SET name = excluded.name, updateTime = excluded.updateTime",
+            )
+            .bind(id)
+            .bind(name)
+            .bind(update_time)
+            .bind(update_time)
+            .execute(&mut *conn)
+            .await?;
+        }
+        Change::GroupDeleted { id, update_time } => {
+            // 分组内容由外键级联删除
+            return delete_entity(conn, "Groups", id, update_time).await;
+        }
+    }
+
+    Ok(true)
+}
+
+/// 内部函数：在一个事务中合并一批修改并保存读取位置
+///
+/// 合并期间设置 applying 标记，触发器不会把这些修改再写入本机发件箱
+async fn apply_entries(
+    pool: &SqlitePool,
+    entries: &[ChangeEntry],
+    offset_key: &str,
+    offset: u64,
+    max_text_bytes: usize,
+) -> AppResult<usize> {
+    let mut tx = pool.begin().await?;
+    suspend_outbox(&mut tx).await?;
+
+    let mut applied = 0;
+    for entry in entries {
+        if apply_change(&mut tx, &entry.change, max_text_bytes).await? {
+            applied += 1;
+        }
+    }
+
+    resume_outbox(&mut tx).await?;
+    set_state(&mut *tx, offset_key, &offset.to_string()).await?;
+    tx.commit().await?;
+
+    Ok(applied)
+}
+
+/// 合并同步目录中其他设备的日志，返回使本地数据发生变化的修改数
+pub async fn import_changes(pool: &SqlitePool, dir: &Path, device: &str, max_text_bytes: usize) -> AppResult<usize> {
+    let entries = fs::read_dir(dir)
+        .map_err(|e| AppError::Io(format!("Failed to read sync folder {}: {}", dir.display(), e)))?;
+
+    let mut imported = 0;
+    for entry in entries {
+        let path = entry?.path();
+        if path.extension() != Some(OsStr::new(LOG_EXTENSION)) {
+            continue;
+        }
+        let Some(remote) = path.file_stem().and_then(OsStr::to_str) else { continue };
+        if remote == device {
+            continue;
+        }
+
+        // 偏移量按同步目录分别记录，更换目录后从头读取新目录中的日志
+        let offset_key = format!("offset:{}:{}", dir.to_string_lossy(), remote);
+        let offset = get_state(pool, &offset_key)
+            .await?
+            .and_then(|offset| offset.parse().ok())
+            .unwrap_or(0);
+        let (changes, new_offset) = read_log(&path, offset)?;
+        if new_offset != offset {
+            imported += apply_entries(pool, &changes, &offset_key, new_offset, max_text_bytes).await?;
+        }
+    }
+
+    Ok(imported)
+}
+
+/// 与同步目录进行一次双向同步：先写出本机修改，再合并其他设备的修改
+pub async fn sync_folder(pool: &SqlitePool, dir: &Path, max_text_bytes: usize) -> AppResult<SyncReport> {
+    fs::create_dir_all(dir)
+        .map_err(|e| AppError::Io(format!("Failed to create sync folder {}: {}", dir.display(), e)))?;
+
+    let device = device_id(pool).await?;
+    let exported = export_changes(pool, dir, &device).await?;
+    let imported = import_changes(pool, dir, &device, max_text_bytes).await?;
+
+    Ok(SyncReport { exported, imported })
+}
+
+/// 内部函数：按配置同步一次，合并了其他设备的修改时通知前端
+async fn run_sync(app: &AppHandle) -> AppResult<SyncReport> {
+    let config = AppConfig::current(app)?;
+    let folder = config
+        .sync_folder
+        .clone()
+        .filter(|folder| !folder.trim().is_empty())
+        .ok_or_else(|| AppError::InvalidInput("Sync folder is not set".to_string()))?;
+
+    let _guard = SYNC_LOCK.lock().await;
+    let pool = db::pool(app).await?;
+    let report = sync_folder(&pool, Path::new(&folder), config.max_text_bytes()).await?;
+
+    if report.imported > 0 {
+        // 合并的记录以未压缩形式保存，按阈值压缩大文本
+        db::compress_large_items(&pool, config.compress_threshold_bytes()).await?;
+        app.emit(SYNC_COMPLETED_EVENT, report)?;
+    }
+    Ok(report)
+}
+
+/// 内部函数：关闭同步时清空发件箱，重新开启时会重新导出全部记录
+async fn reset_outbox(app: &AppHandle) -> AppResult<()> {
+    let pool = db::pool(app).await?;
+    sqlx::query("DELETE FROM SyncOutbox").execute(&pool).await?;
+    delete_state(&pool, STATE_EXPORTED_FOLDER).await
+}
+
+/// 后台定时同步，是否同步和同步间隔由配置实时决定
+pub fn start(app: &AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let interval = AppConfig::current(&app).map_or(60, |config| config.sync_interval_secs);
+            tokio::time::sleep(Duration::from_secs(interval as u64)).await;
+
+            let result = match AppConfig::current(&app) {
+                Ok(config) if config.sync_enabled => run_sync(&app).await.map(|_| ()),
+                Ok(_) => reset_outbox(&app).await,
+                Err(e) => Err(e),
+            };
+            if let Err(e) = result {
+                tracing::error!("Sync failed: {}", e);
+            }
+        }
+    });
+}
+
+/// 立即同步一次
+#[tauri::command]
+pub async fn sync_now(app: AppHandle) -> AppResult<SyncReport> {
+    run_sync(&app).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    use crate::migrations;
+
+    const MAX_TEXT_BYTES: usize = 1024;
+
+    /// 测试用设备：内存数据库和各自的同步目录（模拟 Syncthing 等工具同步的文件夹）
+    struct Device {
+        name: &'static str,
+        pool: SqlitePool,
+        dir: PathBuf,
+    }
+
+    impl Drop for Device {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.dir);
+        }
+    }
+
+    async fn device(name: &'static str) -> Device {
+        let pool = migrations::migrated_pool().await;
+        let dir = std::env::temp_dir().join(format!("cut-sync-test-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        Device { name, pool, dir }
+    }
+
+    /// 内部函数：from 写出修改，日志文件复制到 to 的同步目录后由 to 合并，返回合并的修改数
+    async fn send(from: &Device, to: &Device) -> usize {
+        export_changes(&from.pool, &from.dir, from.name).await.unwrap();
+        let log = format!("{}.{}", from.name, LOG_EXTENSION);
+        if from.dir.join(&log).exists() {
+            fs::copy(from.dir.join(&log), to.dir.join(&log)).unwrap();
+        }
+        import_changes(&to.pool, &to.dir, to.name, MAX_TEXT_BYTES).await.unwrap()
+    }
+
+    async fn add_item(pool: &SqlitePool, id: &str, content: &str, time: &str) {
+        sqlx::query("INSERT INTO CutItems (id, content, createTime, contentHash, preview, length) VALUES (?, ?, ?, ?, ?, ?)")
+            .bind(id)
+            .bind(content)
+            .bind(time)
+            .bind(db::content_hash(content))
+            .bind(db::preview_of(content))
+            .bind(content.chars().count() as i64)
+            .execute(pool)
+            .await
+            .unwrap();
+    }
+
+    async fn edit_item(pool: &SqlitePool, id: &str, content: &str, time: &str) {
+        sqlx::query("UPDATE CutItems SET content = ?, contentHash = ?, updateTime = ? WHERE id = ?")
+            .bind(content)
+            .bind(db::content_hash(content))
+            .bind(time)
+            .bind(id)
+            .execute(pool)
+            .await
+            .unwrap();
+    }
+
+    async fn item_content(pool: &SqlitePool, id: &str) -> Option<String> {
+        sqlx::query_scalar("SELECT content FROM CutItems WHERE id = ?")
+            .bind(id)
+            .fetch_optional(pool)
+            .await
+            .unwrap()
+    }
+
+    async fn deleted_at(pool: &SqlitePool, id: &str) -> Option<String> {
+        sqlx::query_scalar("SELECT deletedAt FROM CutItems WHERE id = ?")
+            .bind(id)
+            .fetch_one(pool)
+            .await
+            .unwrap()
+    }
+
+    async fn outbox_len(pool: &SqlitePool) -> i64 {
+        sqlx::query_scalar("SELECT COUNT(*) FROM SyncOutbox").fetch_one(pool).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn newer_update_wins() {
+        let (a, b) = (device("a").await, device("b").await);
+        add_item(&a.pool, "c1", "原始内容", "2024-01-01T00:00:00.000Z").await;
+        assert_eq!(send(&a, &b).await, 1);
+        assert_eq!(item_content(&b.pool, "c1").await.as_deref(), Some("原始内容"));
+
+        // 两台设备同时修改，无论合并顺序如何都保留较新的修改
+        edit_item(&a.pool, "c1", "设备A的修改", "2024-01-03T00:00:00.000Z").await;
+        edit_item(&b.pool, "c1", "设备B的修改", "2024-01-02T00:00:00.000Z").await;
+        send(&b, &a).await;
+        send(&a, &b).await;
+
+        assert_eq!(item_content(&a.pool, "c1").await.as_deref(), Some("设备A的修改"));
+        assert_eq!(item_content(&b.pool, "c1").await.as_deref(), Some("设备A的修改"));
+    }
+
+    #[tokio::test]
+    async fn oversized_text_is_skipped() {
+        let (a, b) = (device("a").await, device("b").await);
+        add_item(&a.pool, "c1", &"x".repeat(MAX_TEXT_BYTES + 1), "2024-01-01T00:00:00.000Z").await;
+        add_item(&a.pool, "c2", "正常大小", "2024-01-02T00:00:00.000Z").await;
+
+        assert_eq!(send(&a, &b).await, 1);
+        assert_eq!(item_content(&b.pool, "c1").await, None);
+        assert_eq!(item_content(&b.pool, "c2").await.as_deref(), Some("正常大小"));
+    }
+
+    #[tokio::test]
+    async fn deletes_are_kept_as_tombstones() {
+        let (a, b) = (device("a").await, device("b").await);
+        add_item(&a.pool, "c1", "待删除", "2024-01-01T00:00:00.000Z").await;
+        send(&a, &b).await;
+
+        sqlx::query("DELETE FROM CutItems WHERE id = 'c1'").execute(&a.pool).await.unwrap();
+        assert_eq!(send(&a, &b).await, 1);
+        assert_eq!(item_content(&b.pool, "c1").await, None);
+
+        // 从头重新合并日志时，较早的新增不会恢复已删除的记录
+        sqlx::query("DELETE FROM SyncState WHERE key LIKE 'offset:%'").execute(&b.pool).await.unwrap();
+        assert_eq!(import_changes(&b.pool, &b.dir, b.name, MAX_TEXT_BYTES).await.unwrap(), 0);
+        assert_eq!(item_content(&b.pool, "c1").await, None);
+    }
+
+    #[tokio::test]
+    async fn offsets_are_kept_per_folder() {
+        let (b, moved) = (device("b").await, device("moved").await);
+        let added = |id: &str, content: &str| Change::ItemAdded {
+            id: id.to_string(),
+            content: content.to_string(),
+            selection: db::SELECTION_CLIPBOARD.to_string(),
+            create_time: "2024-01-01T00:00:00.000Z".to_string(),
+            update_time: "2024-01-01T00:00:00.000Z".to_string(),
+        };
+        let log = format!("a.{}", LOG_EXTENSION);
+
+        append_changes(&b.dir.join(&log), "a", vec![added("c1", "旧目录")]).unwrap();
+        assert_eq!(import_changes(&b.pool, &b.dir, b.name, MAX_TEXT_BYTES).await.unwrap(), 1);
+
+        // 新目录中同名设备的日志更长，不能沿用旧目录的偏移量跳过开头的修改
+        append_changes(
+            &moved.dir.join(&log),
+            "a",
+            vec![added("c2", "新目录中的第一条修改"), added("c3", "新目录")],
+        )
+        .unwrap();
+        assert_eq!(import_changes(&b.pool, &moved.dir, b.name, MAX_TEXT_BYTES).await.unwrap(), 2);
+        assert_eq!(item_content(&b.pool, "c2").await.as_deref(), Some("新目录中的第一条修改"));
+        assert_eq!(item_content(&b.pool, "c3").await.as_deref(), Some("新目录"));
+    }
+
+    #[tokio::test]
+    async fn trash_and_restore_are_synced() {
+        let (a, b) = (device("a").await, device("b").await);
+        add_item(&a.pool, "c1", "回收站", "2024-01-01T00:00:00.000Z").await;
+        send(&a, &b).await;
+
+        db::trash_item(&a.pool, "c1").await.unwrap();
+        assert_eq!(send(&a, &b).await, 1);
+        assert!(deleted_at(&b.pool, "c1").await.is_some());
+
+        // 修改时间精确到毫秒，避免移入和恢复的时间相同
+        tokio::time::sleep(Duration::from_millis(5)).await;
+        db::restore_trashed_item(&a.pool, "c1").await.unwrap();
+        assert_eq!(send(&a, &b).await, 1);
+        assert_eq!(deleted_at(&b.pool, "c1").await, None);
+        assert_eq!(outbox_len(&b.pool).await, 0);
+    }
+
+    #[tokio::test]
+    async fn grouped_without_group_name_is_recovered() {
+        let b = device("b").await;
+        // 版本17 之前导出的日志：所属分组已不存在时没有分组名称
+        fs::write(
+            b.dir.join(format!("old.{}", LOG_EXTENSION)),
+            concat!(
+                r#"{"device":"old","change":{"op":"grouped","id":"gi1","group_id":"missing","group_name":null,"#,
+                r#""title":null,"content":"孤立内容","create_time":"2024-01-01T00:00:00.000Z","update_time":"2024-01-01T00:00:00.000Z"}}"#,
+                "\n",
+                r#"{"device":"old","change":{"op":"grouped","id":"gi2","group_id":"missing","group_name":"","#,
+                r#""title":null,"content":"孤立内容2","create_time":"2024-01-01T00:00:00.000Z","update_time":"2024-01-01T00:00:00.000Z"}}"#,
+                "\n",
+            ),
+        )
+        .unwrap();
+
+        assert_eq!(import_changes(&b.pool, &b.dir, b.name, MAX_TEXT_BYTES).await.unwrap(), 2);
+        let name: String = sqlx::query_scalar("SELECT name FROM Groups WHERE id = 'missing'")
+            .fetch_one(&b.pool)
+            .await
+            .unwrap();
+        assert_eq!(name, RECOVERED_GROUP_NAME);
+    }
+
+    #[tokio::test]
+    async fn applied_changes_are_not_exported_again() {
+        let (a, b) = (device("a").await, device("b").await);
+        // b 先写出初始快照，之后只写出本机的修改
+        assert_eq!(export_changes(&b.pool, &b.dir, b.name).await.unwrap(), 0);
+
+        add_item(&a.pool, "c1", "来自A", "2024-01-01T00:00:00.000Z").await;
+        assert_eq!(send(&a, &b).await, 1);
+
+        assert_eq!(outbox_len(&b.pool).await, 0);
+        assert_eq!(export_changes(&b.pool, &b.dir, b.name).await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn group_rename_and_delete_are_synced() {
+        let (a, b) = (device("a").await, device("b").await);
+        sqlx::raw_sql(
+            r#"
+            INSERT INTO Groups (id, name, createTime) VALUES ('g1', '工作', '2024-01-01T00:00:00.000Z');
+            INSERT INTO GroupItems (id, groupId, content, createTime) VALUES ('gi1', 'g1', '会议纪要', '2024-01-01T00:00:00.000Z');
+            "#,
+        )
+        .execute(&a.pool)
+        .await
+        .unwrap();
+        send(&a, &b).await;
+
+        sqlx::query("UPDATE Groups SET name = '项目' WHERE id = 'g1'").execute(&a.pool).await.unwrap();
+        assert_eq!(send(&a, &b).await, 1);
+        let name: String = sqlx::query_scalar("SELECT name FROM Groups WHERE id = 'g1'")
+            .fetch_one(&b.pool)
+            .await
+            .unwrap();
+        assert_eq!(name, "项目");
+
+        sqlx::query("DELETE FROM Groups WHERE id = 'g1'").execute(&a.pool).await.unwrap();
+        send(&a, &b).await;
+        let remaining: i64 = sqlx::query_scalar("SELECT (SELECT COUNT(*) FROM Groups) + (SELECT COUNT(*) FROM GroupItems)")
+            .fetch_one(&b.pool)
+            .await
+            .unwrap();
+        assert_eq!(remaining, 0);
+        assert_eq!(outbox_len(&b.pool).await, 0);
+    }
+}
@@ -1,54 +1,38 @@
 use tauri::{
     menu::{Menu, MenuItem},
     tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent},
-    Manager,
     Runtime,
     image::Image
 };
 
+use crate::{logging, window_manager};
+
 pub fn create_tray<R: Runtime>(app: &tauri::AppHandle<R>) -> tauri::Result<()> {
     let quit_i = MenuItem::with_id(app, "quit", "退出", true, None::<&str>)?;
     let show_i = MenuItem::with_id(app, "show", "显示", true, None::<&str>)?;
     let hide_i = MenuItem::with_id(app, "hide", "隐藏", true, None::<&str>)?;
     let settings_i = MenuItem::with_id(app, "settings", "设置", true, None::<&str>)?;
+    let logs_i = MenuItem::with_id(app, "logs", "打开日志目录", true, None::<&str>)?;
     // 分割线
-    let menu = Menu::with_items(app, &[&show_i, &hide_i, &settings_i, &quit_i])?;
-    let tray_icon= Image::from_bytes(include_bytes!("../icons/icon.png"))
-    .expect("Failed to load tray icon");
-    let _ = TrayIconBuilder::with_id("tray")
-        .icon(tray_icon)
+    let menu = Menu::with_items(app, &[&show_i, &hide_i, &settings_i, &logs_i, &quit_i])?;
+    let mut builder = TrayIconBuilder::with_id("tray")
         .menu(&menu)
         .show_menu_on_left_click(false)
-        .on_menu_event(move |app, event| match event.id.as_ref() {
-            "quit" => {
-                app.exit(0);
-            },
-            "show" => {
-                let window = app.get_webview_window("main").unwrap();
-                let _ = window.show();
-            },
-            "hide" => {
-                let window = app.get_webview_window("main").unwrap();
-                let _ = window.hide();
-            },
-            "settings" => {
-                // 打开设置窗口
-                if let Some(window) = app.get_webview_window("settings") {
-                    // 如果设置窗口已存在，显示并聚焦
-                    let _ = window.show();
-                    let _ = window.set_focus();
-                } else {
-                    // 创建新的设置窗口
-                    use tauri::WebviewWindowBuilder;
-                    let _ = WebviewWindowBuilder::new(app, "settings", tauri::WebviewUrl::App("/#/settings".into()))
-                        .title("设置")
-                        .inner_size(600.0, 450.0)
-                        .resizable(true)
-                        .center()
-                        .build();
-                }
-            },
-            _ => {}
+        .on_menu_event(move |app, event| {
+            let result = match event.id.as_ref() {
+                "quit" => {
+                    app.exit(0);
+                    Ok(())
+                },
+                "show" => window_manager::show_main(app),
+                "hide" => window_manager::hide_main(app),
+                "settings" => window_manager::show_settings(app),
+                "logs" => logging::open_log_dir(app),
+                _ => Ok(()),
+            };
+            if let Err(e) = result {
+                tracing::error!("Tray action {} failed: {}", event.id.as_ref(), e);
+            }
         })
         .on_tray_icon_event(|tray, event| {
             if let TrayIconEvent::Click {
@@ -57,15 +41,23 @@ pub fn create_tray<R: Runtime>(app: &tauri::AppHandle<R>) -> tauri::Result<()> {
                 ..
             } = event
             {
-                let app = tray.app_handle();
-                if let Some(window) = app.get_webview_window("main") {
-                    let _ = window.show();
-                    let _ = window.set_focus();
+                if let Err(e) = window_manager::show_main(tray.app_handle()) {
+                    tracing::error!("Failed to show main window: {}", e);
                 }
             }
-        })
-        .build(app);
+        });
 
+    // 图标加载失败时使用应用默认图标
+    match Image::from_bytes(include_bytes!("../icons/icon.png")) {
+        Ok(icon) => builder = builder.icon(icon),
+        Err(e) => {
+            tracing::error!("Failed to load tray icon: {}", e);
+            if let Some(icon) = app.default_window_icon() {
+                builder = builder.icon(icon.clone());
+            }
+        }
+    }
+
+    builder.build(app)?;
     Ok(())
 }
-
@@ -0,0 +1,19 @@
+use tauri::{AppHandle, Emitter, Runtime};
+use tauri_plugin_clipboard_manager::ClipboardExt;
+
+use crate::error::{AppError, AppResult};
+
+/// 由应用自身写入剪切板时通知前端的事件名
+pub const CLIPBOARD_WRITTEN_EVENT: &str = "clipboard-written";
+
+/// 由应用写入文本到剪切板
+///
+/// 写入前先广播事件，前端监控收到后会把该内容当作"上一次内容"，
+/// 避免应用自己写入的内容又被当作新的复制记录
+pub fn write_text<R: Runtime>(app: &AppHandle<R>, text: &str) -> AppResult<()> {
+    let _ = app.emit(CLIPBOARD_WRITTEN_EVENT, text);
+
+    app.clipboard()
+        .write_text(text)
+        .map_err(|e| AppError::Clipboard(format!("Failed to write clipboard: {}", e)))
+}
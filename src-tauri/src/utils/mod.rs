@@ -1 +1,3 @@
-
+pub mod clipboard;
+#[cfg(target_os = "linux")]
+pub mod primary_selection;
@@ -0,0 +1,150 @@
+use std::thread;
+use std::time::{Duration, Instant};
+use tauri::AppHandle;
+use x11_clipboard::{Atom, Clipboard};
+
+use crate::config::AppConfig;
+use crate::db;
+use crate::utils::clipboard;
+
+/// 轮询间隔
+const POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// 记录和同步都关闭时的轮询间隔
+const IDLE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// 读取选区的超时时间
+const LOAD_TIMEOUT: Duration = Duration::from_millis(100);
+
+/// X11 鼠标选区（PRIMARY）监控
+///
+/// 选区内容保持不变超过防抖时间后才记录到历史（selection = primary）或同步到剪切板，
+/// 拖动选择过程中不断变化的内容会被忽略
+struct PrimaryWatcher {
+    app: AppHandle,
+    clipboard: Clipboard,
+    /// 正在等待稳定的选区内容，以及开始出现的时间
+    pending: Option<(String, Instant)>,
+    /// 最近一次已处理的选区内容
+    settled_primary: Option<String>,
+    /// 最近一次读到的剪切板内容
+    last_clipboard: Option<String>,
+}
+
+impl PrimaryWatcher {
+    fn new(app: AppHandle, clipboard: Clipboard) -> Self {
+        // 启动前已存在的选区和剪切板内容不做处理
+        let settled_primary = load_text(&clipboard, clipboard.getter.atoms.primary);
+        let last_clipboard = load_text(&clipboard, clipboard.getter.atoms.clipboard);
+
+        Self {
+            app,
+            clipboard,
+            pending: None,
+            settled_primary,
+            last_clipboard,
+        }
+    }
+
+    /// 内部函数：执行一次检查
+    fn tick(&mut self, config: &AppConfig) {
+        let atoms = &self.clipboard.getter.atoms;
+        let primary = load_text(&self.clipboard, atoms.primary);
+
+        // 内容变化时重新开始计时
+        match (&primary, &self.pending) {
+            (Some(text), Some((pending, _))) if pending == text => {}
+            (Some(text), _) => self.pending = Some((text.clone(), Instant::now())),
+            (None, _) => self.pending = None,
+        }
+
+        let debounce = Duration::from_millis(config.primary_debounce_ms as u64);
+        let settled = match &self.pending {
+            Some((text, since)) if since.elapsed() >= debounce && self.settled_primary.as_ref() != Some(text) => {
+                Some(text.clone())
+            }
+            _ => None,
+        };
+
+        if let Some(text) = settled {
+            self.settled_primary = Some(text.clone());
+
+            if config.primary_capture_enabled {
+                let added = tauri::async_runtime::block_on(db::add_cut_item(&self.app, &text, db::SELECTION_PRIMARY));
+                if let Err(e) = added {
+                    tracing::error!("Failed to save primary selection: {}", e);
+                }
+            }
+
+            if config.primary_sync_enabled && self.last_clipboard.as_ref() != Some(&text) {
+                match clipboard::write_text(&self.app, &text) {
+                    Ok(()) => self.last_clipboard = Some(text),
+                    Err(e) => tracing::error!("Failed to sync primary selection to clipboard: {}", e),
+                }
+            }
+        }
+
+        if config.primary_sync_enabled {
+            self.sync_clipboard_to_primary();
+        }
+    }
+
+    /// 内部函数：剪切板内容变化时写入鼠标选区
+    fn sync_clipboard_to_primary(&mut self) {
+        let atoms = &self.clipboard.getter.atoms;
+        let Some(text) = load_text(&self.clipboard, atoms.clipboard) else { return };
+        if self.last_clipboard.as_ref() == Some(&text) {
+            return;
+        }
+        self.last_clipboard = Some(text.clone());
+
+        if self.settled_primary.as_ref() == Some(&text) {
+            return;
+        }
+        match self.clipboard.store(atoms.primary, atoms.utf8_string, text.as_bytes()) {
+            Ok(()) => {
+                // 视为已处理，避免写入的选区又被记录为新的选区内容
+                self.pending = Some((text.clone(), Instant::now()));
+                self.settled_primary = Some(text);
+            }
+            Err(e) => tracing::error!("Failed to sync clipboard to primary selection: {}", e),
+        }
+    }
+}
+
+/// 内部函数：读取选区文本，选区为空或没有所有者时返回 None
+fn load_text(clipboard: &Clipboard, selection: Atom) -> Option<String> {
+    let atoms = &clipboard.getter.atoms;
+    let bytes = clipboard
+        .load(selection, atoms.utf8_string, atoms.property, LOAD_TIMEOUT)
+        .ok()?;
+    let text = String::from_utf8(bytes).ok()?;
+    (!text.trim().is_empty()).then_some(text)
+}
+
+/// 在后台线程中监控鼠标选区，是否记录和同步由配置实时决定
+///
+/// 无法连接 X11（例如纯 Wayland 会话）时只打印日志
+pub fn start(app: &AppHandle) {
+    let app = app.clone();
+    thread::spawn(move || {
+        let clipboard = match Clipboard::new() {
+            Ok(clipboard) => clipboard,
+            Err(e) => {
+                tracing::warn!("Primary selection is unavailable: {}", e);
+                return;
+            }
+        };
+        let mut watcher = PrimaryWatcher::new(app, clipboard);
+
+        loop {
+            let config = AppConfig::current(&watcher.app).unwrap_or_default();
+            if config.primary_capture_enabled || config.primary_sync_enabled {
+                watcher.tick(&config);
+                thread::sleep(POLL_INTERVAL);
+            } else {
+                thread::sleep(IDLE_INTERVAL);
+            }
+        }
+    });
+}
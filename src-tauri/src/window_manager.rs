@@ -0,0 +1,95 @@
+use tauri::{AppHandle, Manager, Runtime, WebviewUrl, WebviewWindow, WebviewWindowBuilder};
+
+use crate::error::{AppError, AppResult};
+
+/// 主窗口标签（tauri.conf.json 中的窗口没有设置 label，默认为 main）
+pub const MAIN_WINDOW: &str = "main";
+
+/// 设置窗口标签
+pub const SETTINGS_WINDOW: &str = "settings";
+
+/// 显示/隐藏主窗口的全局快捷键
+#[cfg(desktop)]
+const TOGGLE_SHORTCUT: &str = "CommandOrControl+Space";
+
+/// 内部函数：获取主窗口，窗口已关闭时按 tauri.conf.json 中的配置重新创建（隐藏状态）
+fn main_window<R: Runtime>(app: &AppHandle<R>) -> AppResult<WebviewWindow<R>> {
+    if let Some(window) = app.get_webview_window(MAIN_WINDOW) {
+        return Ok(window);
+    }
+
+    tracing::info!("Main window is missing, recreating it");
+    let config = app
+        .config()
+        .app
+        .windows
+        .iter()
+        .find(|config| config.label == MAIN_WINDOW)
+        .cloned()
+        .ok_or_else(|| AppError::Internal("Main window is not configured".to_string()))?;
+    Ok(WebviewWindowBuilder::from_config(app, &config)?.build()?)
+}
+
+/// 内部函数：获取设置窗口，不存在时创建
+fn settings_window<R: Runtime>(app: &AppHandle<R>) -> AppResult<WebviewWindow<R>> {
+    if let Some(window) = app.get_webview_window(SETTINGS_WINDOW) {
+        return Ok(window);
+    }
+
+    Ok(WebviewWindowBuilder::new(app, SETTINGS_WINDOW, WebviewUrl::App("/#/settings".into()))
+        .title("设置")
+        .inner_size(600.0, 450.0)
+        .resizable(true)
+        .center()
+        .build()?)
+}
+
+/// 内部函数：还原、显示并聚焦窗口
+fn reveal<R: Runtime>(window: &WebviewWindow<R>) -> AppResult<()> {
+    window.unminimize()?;
+    window.show()?;
+    window.set_focus()?;
+    Ok(())
+}
+
+/// 显示并聚焦主窗口，窗口已关闭时重新创建
+pub fn show_main<R: Runtime>(app: &AppHandle<R>) -> AppResult<()> {
+    reveal(&main_window(app)?)
+}
+
+/// 隐藏主窗口，窗口不存在时不做处理
+pub fn hide_main<R: Runtime>(app: &AppHandle<R>) -> AppResult<()> {
+    match app.get_webview_window(MAIN_WINDOW) {
+        Some(window) => Ok(window.hide()?),
+        None => Ok(()),
+    }
+}
+
+/// 切换主窗口：可见时隐藏，隐藏、最小化或已关闭时显示并聚焦
+pub fn toggle_main<R: Runtime>(app: &AppHandle<R>) -> AppResult<()> {
+    match app.get_webview_window(MAIN_WINDOW) {
+        Some(window) if window.is_visible()? && !window.is_minimized()? => Ok(window.hide()?),
+        _ => show_main(app),
+    }
+}
+
+/// 打开设置窗口，已存在时显示并聚焦
+pub fn show_settings<R: Runtime>(app: &AppHandle<R>) -> AppResult<()> {
+    reveal(&settings_window(app)?)
+}
+
+/// 注册显示/隐藏主窗口的全局快捷键
+#[cfg(desktop)]
+pub fn register_toggle_shortcut<R: Runtime>(app: &AppHandle<R>) -> AppResult<()> {
+    use tauri_plugin_global_shortcut::{GlobalShortcutExt, ShortcutState};
+
+    app.global_shortcut()
+        .on_shortcut(TOGGLE_SHORTCUT, |app, _shortcut, event| {
+            if event.state == ShortcutState::Released {
+                if let Err(e) = toggle_main(app) {
+                    tracing::error!("Failed to toggle main window: {}", e);
+                }
+            }
+        })
+        .map_err(|e| AppError::System(format!("Failed to register shortcut {}: {}", TOGGLE_SHORTCUT, e)))
+}
@@ -1,24 +1,43 @@
 import { writeText, readText } from '@tauri-apps/plugin-clipboard-manager';
 import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
 import db_service from './db_service';
 
 var intervalId = null
 
 var old_content = ""
 
+// Rust写入剪切板时同步上一次内容，避免应用自己写入的内容被重复记录
+listen('clipboard-written', (event) => {
+    old_content = event.payload;
+});
+
+// 写入后端日志文件，日志写入失败时忽略
+function logError(message, error) {
+    invoke('write_log', { level: 'error', message: `${message}: ${error?.message ?? error}` }).catch(() => {});
+}
+
 // 监控文本
 async function monitorText() {
+    let content;
     try {
-        const content = await readText();
-        if (content && old_content != content) {
-            let item = await db_service.addItem(content);
-            if (item && window.addCutItemToList){
-                window.addCutItemToList(item)
-            }
-            old_content = content;
-        }
+        content = await readText();
     } catch (error) {
         // 如果读取失败，可能是剪切板中没有文本
+        return;
+    }
+    if (!content || old_content == content) {
+        return;
+    }
+    // 保存失败时同样记下内容，避免每秒重复失败
+    old_content = content;
+    try {
+        // 列表由后端的 cut-item-added 事件更新
+        await db_service.addItem(content);
+        // 收集模式下追加到收集缓冲区（保存失败时不追加）
+        await invoke('collect_append', { content });
+    } catch (error) {
+        logError('保存文本失败', error);
     }
 }
 
@@ -35,16 +54,23 @@ async function monitorImage() {
                 content: processedData.base64_data,
                 width: processedData.width,
                 height: processedData.height,
-                size: processedData.original_size
+                size: processedData.original_size,
+                format: processedData.format,
+                byteSize: processedData.byte_size
             });
             
             if (item && window.addImageItemToList) {
                 window.addImageItemToList(item);
             }
+
+            // 后台识别图片文字（未开启OCR时Rust直接返回）
+            if (item) {
+                invoke('ocr_image_item', { id: item.id }).catch(() => {});
+            }
         }
     } catch (error) {
-        // 静默失败，避免日志过多
-        // console.error('监控图片失败:', error);
+        // 同一张图片只处理一次，失败时不会重复记录
+        logError('监控图片失败', error);
     }
 }
 
@@ -68,8 +94,12 @@ function stop() {
     console.log("停止剪切板监控")
 }
 
-async function copyToSystem(content){
+// id 为历史记录或分组内容的ID，复制后记录一次使用（用于按使用频率排序）
+async function copyToSystem(content, id){
     await writeText(content)
+    if (id) {
+        invoke('record_item_usage', { id }).catch(() => {});
+    }
 }
 
 async function copyImageToSystem(base64Data){
@@ -7,7 +7,8 @@ var db = null;
 // 默认配置
 let config = {
   max_text_history: 500,
-  max_image_history: 30
+  max_image_history: 30,
+  history_sort: 'recent'
 };
 
 // 配置加载状态
@@ -47,42 +48,16 @@ export default {
     }
   },
 
+  // 由后端记录文本：相同内容只更新已有记录（移到最前并增加记录次数），并按配置清理超出的历史
+  // 保存失败时抛出错误，由调用方记录日志
   async addItem(content) {
-    await this.init();
-    const id = uuidv4(); // 生成 UUID
-    const createTime = new Date().toISOString(); // 当前时间戳
-    
-    try {
-      await db.execute(
-        'INSERT INTO CutItems (id, content, createTime) VALUES (?, ?, ?)',
-        [id, content, createTime]
-      );
-      
-      // 检查记录总数，如果超过上限，删除最旧的记录
-      const countResult = await db.select('SELECT COUNT(*) as count FROM CutItems');
-      const totalCount = countResult[0].count;
-      
-      if (totalCount > config.max_text_history) {
-        const deleteCount = totalCount - config.max_text_history;
-        await db.execute(
-          `DELETE FROM CutItems WHERE id IN (
-            SELECT id FROM CutItems ORDER BY createTime ASC LIMIT ?
-          )`,
-          [deleteCount]
-        );
-      }
-      
-      return {"id":id,"content":content,"createTime":createTime}
-    } catch (error) {
-      console.error('Error adding item:', error);
-      return null;
-    }
+    return await invoke('add_text_item', { content });
   },
 
+  // 由后端按配置的排序方式查询，不读取压缩后的完整内容
   async fetchItems() {
-    await this.init();
     try {
-      const result = await db.select('SELECT * FROM CutItems order by createTime desc');
+      const result = await invoke('list_text_items');
       return result || [];
     } catch (error) {
       console.error('Error fetching items:', error);
@@ -90,10 +65,18 @@ export default {
     }
   },
 
+  // 读取记录的完整内容：压缩存储的大文本列表中只有预览
+  async getFullContent(item) {
+    if (!item.compressed) {
+      return item.content;
+    }
+    return await invoke('get_item_content', { id: item.id });
+  },
+
+  // 移到回收站，后端发出 item-trashed 事件
   async removeItem(id) {
-    await this.init();
     try {
-      await db.execute('DELETE FROM CutItems WHERE id =?',[id]);
+      await invoke('delete_item', { id });
     } catch (error) {
       console.error('Error fetching items:', error);
     }
@@ -107,23 +90,12 @@ export default {
     
     try {
       await db.execute(
-        'INSERT INTO ImageItems (id, content, width, height, size, createTime) VALUES (?, ?, ?, ?, ?, ?)',
-        [id, imageData.content, imageData.width, imageData.height, imageData.size, createTime]
+        'INSERT INTO ImageItems (id, content, width, height, size, format, byteSize, createTime) VALUES (?, ?, ?, ?, ?, ?, ?, ?)',
+        [id, imageData.content, imageData.width, imageData.height, imageData.size, imageData.format, imageData.byteSize, createTime]
       );
       
-      // 检查记录总数，如果超过上限，删除最旧的记录
-      const countResult = await db.select('SELECT COUNT(*) as count FROM ImageItems');
-      const totalCount = countResult[0].count;
-      
-      if (totalCount > config.max_image_history) {
-        const deleteCount = totalCount - config.max_image_history;
-        await db.execute(
-          `DELETE FROM ImageItems WHERE id IN (
-            SELECT id FROM ImageItems ORDER BY createTime ASC LIMIT ?
-          )`,
-          [deleteCount]
-        );
-      }
+      // 由后端按保留数量删除最旧的记录（与文本记录相同，回收站中的记录不计入数量）
+      await invoke('enforce_history_limits');
       
       return {
         id,
@@ -131,6 +103,8 @@ export default {
         width: imageData.width,
         height: imageData.height,
         size: imageData.size,
+        format: imageData.format,
+        byteSize: imageData.byteSize,
         createTime
       };
     } catch (error) {
@@ -142,7 +116,7 @@ export default {
   async fetchImageItems() {
     await this.init();
     try {
-      const result = await db.select('SELECT * FROM ImageItems ORDER BY createTime DESC');
+      const result = await db.select('SELECT * FROM ImageItems WHERE deletedAt IS NULL ORDER BY createTime DESC');
       return result || [];
     } catch (error) {
       console.error('Error fetching image items:', error);
@@ -150,10 +124,21 @@ export default {
     }
   },
 
+  // 按 OCR 识别出的文字搜索图片（未识别过的图片不会出现在结果中）
+  async searchImageItems(keyword) {
+    try {
+      const result = await invoke('search_image_items', { keyword });
+      return result || [];
+    } catch (error) {
+      console.error('Error searching image items:', error);
+      return [];
+    }
+  },
+
+  // 移到回收站，后端发出 item-trashed 事件
   async removeImageItem(id) {
-    await this.init();
     try {
-      await db.execute('DELETE FROM ImageItems WHERE id = ?', [id]);
+      await invoke('delete_item', { id });
     } catch (error) {
       console.error('Error removing image item:', error);
     }
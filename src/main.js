@@ -4,7 +4,6 @@ import App from "./App.vue";
 
 import router from './router/router'
 import { start } from './cut_service'
-import {init_hotkey} from './hotkey'
 import { getCurrentWebviewWindow } from '@tauri-apps/api/webviewWindow';
 
 window.addEventListener('error', (event) => {
@@ -16,9 +15,8 @@ async function main() {
     const currentWindow = getCurrentWebviewWindow();
     const windowLabel = currentWindow.label;
     
-    // 只在主窗口中初始化快捷键和剪贴板监控
+    // 只在主窗口中初始化剪贴板监控（显示/隐藏快捷键由 Rust 注册）
     if (windowLabel === 'main') {
-        await init_hotkey();
         start();
     }
     
@@ -1,3 +1,4 @@
+import { h } from 'vue';
 import { message } from 'ant-design-vue';
 
 export function showMessageShort(content) {
@@ -9,4 +10,23 @@ export function showMessageShort(content) {
         rtl: true,
         prefixCls: 'ant-message',
       });
-}
\ No newline at end of file
+}
+
+// 带「撤销」按钮的提示，点击后调用 onUndo
+export function showUndoMessage(content, onUndo) {
+    const key = `undo-${Date.now()}`;
+    message.info({
+        key,
+        duration: 5,
+        content: h('span', [
+            content,
+            h('a', {
+                style: 'margin-left: 12px',
+                onClick: () => {
+                    message.destroy(key);
+                    onUndo();
+                },
+            }, '撤销'),
+        ]),
+    });
+}